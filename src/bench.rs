@@ -0,0 +1,334 @@
+//! Benchmark history
+//!
+//! `bench` runs every part several times and appends the timings to a history file, so
+//! `compare` can tell which parts got slower between two runs. The history has one line per
+//! part and run, with tab separated fields:
+//!
+//! ```text
+//! run  timestamp  commit  day  part  samples  min_us  median_us  mean_us
+//! ```
+//!
+//! Runs are numbered from 1 in the order they were appended, the timestamp is in seconds since
+//! the epoch and the commit is read from `.git` in the current directory, or `unknown` outside
+//! of a checkout.
+
+use std::{
+    fmt::{Display, Write as _},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::days::Day;
+use crate::params::Params;
+
+/// The timings of a part in microseconds
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Timing {
+    day: u8,
+    part: usize,
+    samples: usize,
+    min: u64,
+    median: u64,
+    mean: u64,
+}
+
+impl Display for Timing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "d{:2}.{}: min {:>10}  median {:>10}  mean {:>10}",
+            self.day,
+            self.part,
+            time(self.min),
+            time(self.median),
+            time(self.mean)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Run {
+    pub(crate) id: usize,
+    timestamp: u64,
+    commit: String,
+    timings: Vec<Timing>,
+}
+
+pub(crate) struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All runs in the order they were appended, none if there is no history yet
+    pub(crate) fn load(&self) -> io::Result<Vec<Run>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut runs: Vec<Run> = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {} of {} is malformed", i + 1, self.path.display()),
+                )
+            };
+            let fields: Vec<_> = line.split('\t').collect();
+            let [id, timestamp, commit, day, part, samples, min, median, mean] = fields[..] else {
+                return Err(invalid());
+            };
+            let number = |field: &str| field.parse::<u64>().map_err(|_| invalid());
+            let timing = Timing {
+                day: day.parse().map_err(|_| invalid())?,
+                part: number(part)? as usize,
+                samples: number(samples)? as usize,
+                min: number(min)?,
+                median: number(median)?,
+                mean: number(mean)?,
+            };
+
+            let id = number(id)? as usize;
+            match runs.last_mut() {
+                Some(run) if run.id == id => run.timings.push(timing),
+                _ => runs.push(Run {
+                    id,
+                    timestamp: number(timestamp)?,
+                    commit: commit.to_string(),
+                    timings: vec![timing],
+                }),
+            }
+        }
+        Ok(runs)
+    }
+
+    fn append(&self, run: &Run) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut lines = String::new();
+        for t in &run.timings {
+            let _ = writeln!(
+                lines,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                run.id,
+                run.timestamp,
+                run.commit,
+                t.day,
+                t.part,
+                t.samples,
+                t.min,
+                t.median,
+                t.mean
+            );
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(lines.as_bytes())
+    }
+}
+
+/// The commit checked out in `repo`, read from `.git` without running git
+fn commit(repo: &Path) -> Option<String> {
+    let git = repo.join(".git");
+    let head = fs::read_to_string(git.join("HEAD")).ok()?;
+    let Some(name) = head.trim().strip_prefix("ref: ") else {
+        // a detached head is the commit itself
+        return Some(head.trim().to_string());
+    };
+    if let Ok(commit) = fs::read_to_string(git.join(name)) {
+        return Some(commit.trim().to_string());
+    }
+    // the ref was packed by git gc
+    fs::read_to_string(git.join("packed-refs"))
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_suffix(name)?.strip_suffix(' ').map(String::from))
+}
+
+/// Runs `part` of `day` on `inp` `samples` times, None if it panics or fails
+pub(crate) fn measure(
+    day: &Day,
+    inp: &str,
+    params: &Params,
+    part: usize,
+    samples: usize,
+) -> Option<Timing> {
+    let mut times = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let now = Instant::now();
+        panic::catch_unwind(AssertUnwindSafe(|| day.parts[part - 1](inp, params)))
+            .ok()?
+            .ok()?;
+        times.push(now.elapsed().as_micros() as u64);
+    }
+    times.sort_unstable();
+
+    Some(Timing {
+        day: day.day,
+        part,
+        samples,
+        min: times[0],
+        median: times[samples / 2],
+        mean: times.iter().sum::<u64>() / samples as u64,
+    })
+}
+
+/// Times every part of `days` `samples` times, appends the run to `history` and returns it
+pub(crate) fn bench<'a>(
+    history: &History,
+    days: impl Iterator<Item = &'a Day>,
+    samples: usize,
+) -> io::Result<Run> {
+    let runs = history.load()?;
+    let mut run = Run {
+        id: runs.last().map_or(1, |r| r.id + 1),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        commit: commit(Path::new(".")).unwrap_or("unknown".into()),
+        timings: Vec::new(),
+    };
+
+    for day in days {
+        let params = Params::new(day.params);
+        for part in 1..=day.parts.len() {
+            match measure(day, day.input, &params, part, samples) {
+                Some(t) => {
+                    println!("{t}");
+                    run.timings.push(t);
+                }
+                None => println!("d{:2}.{part}: failed, skipped", day.day),
+            }
+        }
+    }
+
+    history.append(&run)?;
+    Ok(run)
+}
+
+/// A time in microseconds with a readable unit
+pub(crate) fn time(us: u64) -> String {
+    match us {
+        0..1_000 => format!("{us}us"),
+        1_000..1_000_000 => format!("{:.1}ms", us as f64 / 1e3),
+        _ => format!("{:.2}s", us as f64 / 1e6),
+    }
+}
+
+/// The change of the median time of every part from run `a` to run `b`, changes below
+/// `threshold` percent are considered noise
+pub(crate) fn compare(a: &Run, b: &Run, threshold: f64) -> String {
+    let mut report = format!(
+        "run {} ({}) -> run {} ({})\n",
+        a.id,
+        &a.commit[..a.commit.len().min(7)],
+        b.id,
+        &b.commit[..b.commit.len().min(7)]
+    );
+    let (mut faster, mut slower, mut same) = (0, 0, 0);
+
+    for new in &b.timings {
+        let label = format!("d{:2}.{}", new.day, new.part);
+        let Some(old) = a
+            .timings
+            .iter()
+            .find(|t| (t.day, t.part) == (new.day, new.part))
+        else {
+            let _ = writeln!(report, "{label}: only in run {}", b.id);
+            continue;
+        };
+
+        let change = (new.median as f64 / (old.median as f64).max(1.) - 1.) * 100.;
+        let verdict = match change {
+            c if c.abs() < threshold => {
+                same += 1;
+                "~"
+            }
+            c if c < 0. => {
+                faster += 1;
+                "faster"
+            }
+            _ => {
+                slower += 1;
+                "SLOWER"
+            }
+        };
+        let _ = writeln!(
+            report,
+            "{label}: {:>10} -> {:>10}  {change:+7.1}%  {verdict}",
+            time(old.median),
+            time(new.median)
+        );
+    }
+    for old in &a.timings {
+        if !b
+            .timings
+            .iter()
+            .any(|t| (t.day, t.part) == (old.day, old.part))
+        {
+            let _ = writeln!(report, "d{:2}.{}: only in run {}", old.day, old.part, a.id);
+        }
+    }
+
+    let _ = write!(
+        report,
+        "{faster} faster, {slower} slower, {same} within {threshold}%"
+    );
+    report
+}
+
+#[test]
+fn test_history() {
+    let timing = |day, part, median| Timing {
+        day,
+        part,
+        samples: 5,
+        min: median - 10,
+        median,
+        mean: median + 10,
+    };
+    let history = History::new(
+        std::env::temp_dir().join(format!("aoc23-bench-{}/history", std::process::id())),
+    );
+    let a = Run {
+        id: 1,
+        timestamp: 1700000000,
+        commit: "7b6eef9a".to_string(),
+        timings: vec![timing(14, 1, 200), timing(14, 2, 1000), timing(16, 1, 500)],
+    };
+    let b = Run {
+        id: 2,
+        timestamp: 1700000100,
+        commit: "da98439b".to_string(),
+        timings: vec![timing(14, 1, 202), timing(14, 2, 400), timing(16, 1, 600)],
+    };
+    history.append(&a).unwrap();
+    history.append(&b).unwrap();
+    assert_eq!(history.load().unwrap(), [a.clone(), b.clone()]);
+    fs::remove_dir_all(history.path().parent().unwrap()).unwrap();
+
+    assert_eq!(
+        compare(&a, &b, 5.),
+        "\
+run 1 (7b6eef9) -> run 2 (da98439)
+d14.1:      200us ->      202us     +1.0%  ~
+d14.2:      1.0ms ->      400us    -60.0%  faster
+d16.1:      500us ->      600us    +20.0%  SLOWER
+1 faster, 1 slower, 1 within 5%"
+    );
+}
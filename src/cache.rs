@@ -0,0 +1,130 @@
+//! Cache of the answers
+//!
+//! Answers are stored on disk, one file per answer, named after the day, the part and a hash of
+//! everything the answer depends on: the build of the solvers, the input and the parameters.
+//! Running the days again with the same input then only has to read the answers back, while a
+//! rebuilt binary, whose solvers may have changed, solves them again.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::days::Answer;
+use crate::params::Params;
+
+pub(crate) struct Cache {
+    dir: PathBuf,
+    /// identifies the build of the solvers, none if the executable can't be read
+    build: OnceLock<Option<u64>>,
+}
+
+// 64 bit FNV-1a, unlike the hasher of std it is guaranteed to stay the same between builds
+fn hash(parts: &[&[u8]]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for part in parts {
+        // the length separates the parts, so moving bytes between them changes the hash
+        for byte in part.len().to_le_bytes().iter().chain(part.iter()) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+// a hash of the running executable, which changes with every change of the code
+fn build_hash() -> Option<u64> {
+    let exe = fs::read(std::env::current_exe().ok()?).ok()?;
+    Some(hash(&[&exe]))
+}
+
+impl Cache {
+    pub(crate) fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            build: OnceLock::new(),
+        }
+    }
+
+    fn path(&self, day: u8, part: usize, inp: &str, params: &Params) -> Option<PathBuf> {
+        let build = (*self.build.get_or_init(build_hash))?;
+        let key = hash(&[
+            &build.to_le_bytes(),
+            inp.as_bytes(),
+            params.to_string().as_bytes(),
+        ]);
+        Some(self.dir.join(format!("d{day:02}.{part}-{key:016x}")))
+    }
+
+    /// The cached answer of a part, if there is one
+    pub(crate) fn get(&self, day: u8, part: usize, inp: &str, params: &Params) -> Option<Answer> {
+        fs::read_to_string(self.path(day, part, inp, params)?)
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    pub(crate) fn put(
+        &self,
+        day: u8,
+        part: usize,
+        inp: &str,
+        params: &Params,
+        answer: &Answer,
+    ) -> io::Result<()> {
+        // without a build to tell the answers apart nothing is cached
+        let Some(path) = self.path(day, part, inp, params) else {
+            return Ok(());
+        };
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, format!("{answer}\n"))
+    }
+
+    /// Removes all cached answers
+    pub(crate) fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[test]
+fn test_cache() {
+    use crate::params::{self, Param};
+
+    static DECLARED: &[Param] = &[Param {
+        name: "steps",
+        default: "64",
+        help: "number of steps",
+        validate: params::count,
+    }];
+
+    let cache =
+        Cache::new(std::env::temp_dir().join(format!("aoc23-cache-{}", std::process::id())));
+    let mut params = Params::new(DECLARED);
+    cache.put(21, 1, "S.#", &params, &Answer::Int(42)).unwrap();
+
+    assert_eq!(cache.get(21, 1, "S.#", &params), Some(Answer::Int(42)));
+    assert_eq!(cache.get(21, 2, "S.#", &params), None);
+    assert_eq!(cache.get(21, 1, "S#.", &params), None);
+    params.set("steps", "6").unwrap();
+    assert_eq!(cache.get(21, 1, "S.#", &params), None);
+
+    // the answers of another build are solved again
+    let rebuilt = Cache {
+        dir: cache.dir().to_path_buf(),
+        build: OnceLock::from(Some(1)),
+    };
+    assert_eq!(rebuilt.get(21, 1, "S.#", &Params::new(DECLARED)), None);
+
+    cache.clear().unwrap();
+    assert!(!cache.dir().exists());
+    cache.clear().unwrap();
+}
@@ -0,0 +1,717 @@
+//! Command line parsing
+//!
+
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use crate::util::{
+    explain,
+    frames::{FrameCapture, Target},
+    graph::Format,
+};
+
+pub(crate) static USAGE: &str = "\
+usage: aoc23 [options]                      run all days
+       aoc23 run <day> [--param NAME=VALUE]... [options]
+                                            run a single day, with --help to list its parameters
+       aoc23 run [<day>] --example [N]      run a day, or all days, on their Nth example
+       aoc23 run <day> --explain [text|json]
+                                            show how the answers are derived (4, 7, 13, 19)
+       aoc23 gen <day> [--seed S] [--size N]
+                                            print a random input for <day>
+       aoc23 check <day> --input FILE       check the format of an input for <day>
+       aoc23 scale <day> [--factor K] [--input FILE]
+                                            print the input of <day> made K times bigger
+       aoc23 watch <day> [--part N]... [--input FILE] [--param NAME=VALUE]...
+                                            run a day again whenever its input changes
+       aoc23 stream <day> [--part N]... [--input FILE] [--param NAME=VALUE]...
+                                            solve an input too big for memory from FILE or stdin,
+                                            line by line (1, 2, 4, 7, 9, 12, 15, 18)
+       aoc23 cache clear                    remove all cached answers
+       aoc23 serve [--port N]               answer HTTP requests for the solutions on localhost
+       aoc23 bench [<day>] [--runs N]       time the parts and add the timings to the history
+       aoc23 compare <run> <run> [--threshold PCT]
+                                            compare the timings of two runs of bench
+       aoc23 report [--markdown] [--redact] [--timeout SECS]
+                                            list the answers and times of all days
+       aoc23 graph <day> [--format dot|graphml] [--input FILE]
+                                            print the graph of the input of <day> (8, 20, 23, 25)
+       aoc23 repl                           load inputs and run parts interactively, see help in it
+
+options:
+    --frames DIR          write the frames of the simulations (d14, d16, d22) into DIR
+    --frames-log FILE     write all frames into a single replayable log FILE
+    --frame-limit N       write at most N frames per simulation
+    --frame-stride N      only write every Nth frame
+    --param NAME=VALUE    override a parameter of the day
+    --explain [F]         print the derivation of every answer as text (default) or json
+    --example [N]         run the Nth example of the puzzle (the first by default) with the
+                          parameters it is given for instead of the input
+    --checked             report arithmetic overflows with the day, part and operation
+    --no-cache            solve every part again instead of using the cached answers
+    --timeout SECS        give up on a part after SECS seconds
+    --memory              report the peak memory usage and the allocations of each part
+    --seed S              seed of the input generator, random by default
+    --size N              size of the generated input (10 by default)
+    --input FILE          input to check or stream, or to watch, graph or scale instead of the
+                          input of the day
+    --factor K            number of copies of the scaled input, per direction for grids (2 by
+                          default)
+    --format F            format of the graph, dot (default) or graphml
+    --part N              only run part N of the watched or streamed day
+    --port N              port to serve on (8023 by default)
+    --runs N              time each part N times (5 by default)
+    --threshold PCT       changes below PCT percent are noise (5 by default)
+    --markdown            print the report as a markdown table
+    --redact              leave the answers out of the report
+    -v, -vv               log debug or trace messages of the solvers, AOC_LOG=d22=trace sets
+                          the level per day
+    -h, --help            print this message";
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) enum Command {
+    /// run all the days
+    #[default]
+    All,
+    /// generate an input
+    Gen {
+        day: u8,
+        seed: Option<usize>,
+        size: usize,
+    },
+    /// run a single day
+    Run {
+        day: u8,
+        /// overridden parameters as (name, value)
+        params: Vec<(String, String)>,
+    },
+    /// check the format of an input
+    Check { day: u8, input: PathBuf },
+    /// make an input bigger
+    Scale {
+        day: u8,
+        /// the input of the day if not given
+        input: Option<PathBuf>,
+        factor: usize,
+    },
+    /// run a day whenever its input changes
+    Watch {
+        day: u8,
+        /// the watched file, the input of the day if not given
+        input: Option<PathBuf>,
+        /// the parts to run, all if empty
+        parts: Vec<usize>,
+        /// overridden parameters as (name, value)
+        params: Vec<(String, String)>,
+    },
+    /// solve an input without reading all of it into memory
+    Stream {
+        day: u8,
+        /// stdin if not given
+        input: Option<PathBuf>,
+        /// the parts to run, all if empty
+        parts: Vec<usize>,
+        /// overridden parameters as (name, value)
+        params: Vec<(String, String)>,
+    },
+    /// remove the cached answers
+    ClearCache,
+    /// serve the solutions over HTTP
+    Serve { port: u16 },
+    /// time the parts of a day, or all days
+    Bench { day: Option<u8>, runs: usize },
+    /// compare two benchmark runs
+    Compare { a: usize, b: usize, threshold: f64 },
+    /// list the answers and times of all days
+    Report { markdown: bool, redact: bool },
+    /// write the graph of an input
+    Graph {
+        day: u8,
+        /// the input of the day if not given
+        input: Option<PathBuf>,
+        format: Format,
+    },
+    /// run parts interactively
+    Repl,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Options {
+    pub(crate) command: Command,
+    pub(crate) frames: Option<FrameCapture>,
+    pub(crate) checked: bool,
+    pub(crate) no_cache: bool,
+    /// run the example with this number instead of the input
+    pub(crate) example: Option<usize>,
+    /// print the derivation of the answers in this format
+    pub(crate) explain: Option<explain::Format>,
+    /// count the allocations of each part
+    pub(crate) memory: bool,
+    /// time limit of each part
+    pub(crate) timeout: Option<Duration>,
+    /// number of times -v was given
+    pub(crate) verbosity: u8,
+    pub(crate) help: bool,
+}
+
+fn value<T: FromStr>(flag: &str, args: &mut impl Iterator<Item = String>) -> Result<T, String> {
+    let arg = args
+        .next()
+        .ok_or_else(|| format!("{flag} requires a value"))?;
+    arg.parse()
+        .map_err(|_| format!("invalid value for {flag}: {arg}"))
+}
+
+pub(crate) fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut args = args.peekable();
+    let mut options = Options::default();
+    let mut target = None;
+    let mut limit = None;
+    let mut stride = None;
+    let mut seed = None;
+    let mut size = None;
+    let mut factor = None;
+    let mut input = None;
+    let mut port = None;
+    let mut runs = None;
+    let mut threshold = None;
+    let mut markdown = false;
+    let mut format = None;
+    let mut redact = false;
+    let mut params = Vec::new();
+    let mut parts = Vec::new();
+    let mut positional = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--frames" => target = Some(Target::Dir(value::<PathBuf>(&arg, &mut args)?)),
+            "--frames-log" => target = Some(Target::Log(value::<PathBuf>(&arg, &mut args)?)),
+            "--frame-limit" => limit = Some(value::<usize>(&arg, &mut args)?),
+            "--frame-stride" => stride = Some(value::<usize>(&arg, &mut args)?),
+            "--seed" => seed = Some(value::<usize>(&arg, &mut args)?),
+            "--size" => size = Some(value::<usize>(&arg, &mut args)?),
+            "--factor" => match value::<usize>(&arg, &mut args)? {
+                0 => return Err("invalid value for --factor: 0".into()),
+                k => factor = Some(k),
+            },
+            "--input" => input = Some(value::<PathBuf>(&arg, &mut args)?),
+            "--part" => parts.push(value::<usize>(&arg, &mut args)?),
+            "--port" => port = Some(value::<u16>(&arg, &mut args)?),
+            "--runs" => match value::<usize>(&arg, &mut args)? {
+                0 => return Err("invalid value for --runs: 0".into()),
+                n => runs = Some(n),
+            },
+            "--threshold" => threshold = Some(value::<f64>(&arg, &mut args)?),
+            "--markdown" => markdown = true,
+            "--format" => format = Some(value::<Format>(&arg, &mut args)?),
+            "--redact" => redact = true,
+            "--param" => {
+                let param = value::<String>(&arg, &mut args)?;
+                match param.split_once('=') {
+                    Some((name, value)) => params.push((name.to_string(), value.to_string())),
+                    None => return Err(format!("invalid value for --param: {param}")),
+                }
+            }
+            // the number is optional, so it's only taken if the next argument is one
+            "--example" => match args.next_if(|a| a.parse::<usize>().is_ok()) {
+                Some(n) if n == "0" => return Err("invalid value for --example: 0".into()),
+                Some(n) => options.example = n.parse().ok(),
+                None => options.example = Some(1),
+            },
+            "--explain" => {
+                let format = args.next_if(|a| a.parse::<explain::Format>().is_ok());
+                options.explain =
+                    Some(format.map_or(explain::Format::Text, |f| f.parse().unwrap()));
+            }
+            "--checked" => options.checked = true,
+            "--no-cache" => options.no_cache = true,
+            "--memory" => options.memory = true,
+            "--timeout" => {
+                let secs = value::<f64>(&arg, &mut args)?;
+                match Duration::try_from_secs_f64(secs) {
+                    Ok(timeout) if secs > 0. => options.timeout = Some(timeout),
+                    _ => return Err(format!("invalid value for --timeout: {secs}")),
+                }
+            }
+            "-v" => options.verbosity += 1,
+            "-vv" => options.verbosity += 2,
+            "-h" | "--help" => options.help = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown argument: {arg}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    options.command = match positional.iter().map(|s| s.as_str()).collect::<Vec<_>>()[..] {
+        [] => Command::All,
+        ["run", day] => Command::Run {
+            day: day.parse().map_err(|_| format!("invalid day: {day}"))?,
+            params: std::mem::take(&mut params),
+        },
+        ["run"] if options.example.is_some() => Command::All,
+        ["run"] => return Err("run requires a day".into()),
+        ["gen", day] => Command::Gen {
+            day: day.parse().map_err(|_| format!("invalid day: {day}"))?,
+            seed,
+            size: size.unwrap_or(10),
+        },
+        ["gen"] => return Err("gen requires a day".into()),
+        ["check", day] => Command::Check {
+            day: day.parse().map_err(|_| format!("invalid day: {day}"))?,
+            input: input.take().ok_or("check requires --input")?,
+        },
+        ["check"] => return Err("check requires a day".into()),
+        ["scale", day] => Command::Scale {
+            day: day.parse().map_err(|_| format!("invalid day: {day}"))?,
+            input: input.take(),
+            factor: factor.take().unwrap_or(2),
+        },
+        ["scale"] => return Err("scale requires a day".into()),
+        ["watch", day] => Command::Watch {
+            day: day.parse().map_err(|_| format!("invalid day: {day}"))?,
+            input: input.take(),
+            parts: std::mem::take(&mut parts),
+            params: std::mem::take(&mut params),
+        },
+        ["watch"] => return Err("watch requires a day".into()),
+        ["stream", day] => Command::Stream {
+            day: day.parse().map_err(|_| format!("invalid day: {day}"))?,
+            input: input.take(),
+            parts: std::mem::take(&mut parts),
+            params: std::mem::take(&mut params),
+        },
+        ["stream"] => return Err("stream requires a day".into()),
+        ["cache", "clear"] => Command::ClearCache,
+        ["serve"] => Command::Serve {
+            port: port.take().unwrap_or(8023),
+        },
+        ["bench"] => Command::Bench {
+            day: None,
+            runs: runs.take().unwrap_or(5),
+        },
+        ["bench", day] => Command::Bench {
+            day: Some(day.parse().map_err(|_| format!("invalid day: {day}"))?),
+            runs: runs.take().unwrap_or(5),
+        },
+        ["compare", a, b] => Command::Compare {
+            a: a.parse().map_err(|_| format!("invalid run: {a}"))?,
+            b: b.parse().map_err(|_| format!("invalid run: {b}"))?,
+            threshold: threshold.take().unwrap_or(5.),
+        },
+        ["compare", ..] => return Err("compare requires two runs".into()),
+        ["graph", day] => Command::Graph {
+            day: day.parse().map_err(|_| format!("invalid day: {day}"))?,
+            input: input.take(),
+            format: format.take().unwrap_or(Format::Dot),
+        },
+        ["graph"] => return Err("graph requires a day".into()),
+        ["repl"] => Command::Repl,
+        ["report"] => Command::Report {
+            markdown: std::mem::take(&mut markdown),
+            redact: std::mem::take(&mut redact),
+        },
+        _ => return Err(format!("unknown command: {}", positional.join(" "))),
+    };
+    if !matches!(options.command, Command::Gen { .. }) && (seed.is_some() || size.is_some()) {
+        return Err("--seed and --size can only be used with gen".into());
+    }
+    if options.checked
+        && !matches!(
+            options.command,
+            Command::All
+                | Command::Run { .. }
+                | Command::Watch { .. }
+                | Command::Serve { .. }
+                | Command::Repl
+        )
+    {
+        return Err("--checked can only be used when running the days".into());
+    }
+    if options.no_cache && !matches!(options.command, Command::All | Command::Run { .. }) {
+        return Err("--no-cache can only be used when running the days".into());
+    }
+    if options.timeout.is_some()
+        && !matches!(
+            options.command,
+            Command::All
+                | Command::Run { .. }
+                | Command::Watch { .. }
+                | Command::Serve { .. }
+                | Command::Report { .. }
+                | Command::Repl
+        )
+    {
+        return Err("--timeout can only be used when running the days".into());
+    }
+    if options.example.is_some() && !matches!(options.command, Command::All | Command::Run { .. }) {
+        return Err("--example can only be used with run".into());
+    }
+    if options.explain.is_some() && !matches!(options.command, Command::Run { .. }) {
+        return Err("--explain can only be used with run".into());
+    }
+    if options.memory
+        && !matches!(
+            options.command,
+            Command::All | Command::Run { .. } | Command::Stream { .. }
+        )
+    {
+        return Err("--memory can only be used when running the days".into());
+    }
+    if !params.is_empty() {
+        return Err("--param can only be used with run, watch and stream".into());
+    }
+    if input.is_some() {
+        return Err("--input can only be used with check, stream, watch, graph and scale".into());
+    }
+    if factor.is_some() {
+        return Err("--factor can only be used with scale".into());
+    }
+    if format.is_some() {
+        return Err("--format can only be used with graph".into());
+    }
+    if !parts.is_empty() {
+        return Err("--part can only be used with watch and stream".into());
+    }
+    if port.is_some() {
+        return Err("--port can only be used with serve".into());
+    }
+    if runs.is_some() {
+        return Err("--runs can only be used with bench".into());
+    }
+    if threshold.is_some() {
+        return Err("--threshold can only be used with compare".into());
+    }
+    if markdown || redact {
+        return Err("--markdown and --redact can only be used with report".into());
+    }
+
+    options.frames = match (target, limit, stride) {
+        (Some(target), limit, stride) => {
+            let mut capture = FrameCapture::new(target);
+            if let Some(limit) = limit {
+                capture = capture.limit(limit);
+            }
+            if let Some(stride) = stride {
+                capture = capture.stride(stride);
+            }
+            Some(capture)
+        }
+        (None, None, None) => None,
+        _ => {
+            return Err("--frame-limit and --frame-stride require --frames or --frames-log".into())
+        }
+    };
+
+    Ok(options)
+}
+
+#[test]
+fn test_parse_frames() {
+    let args = ["--frames-log", "frames.log", "--frame-stride", "10"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert!(options.frames.is_some());
+
+    let args = ["--frame-limit", "10"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_checked() {
+    let options = parse(["--checked".to_string()].into_iter()).unwrap();
+    assert!(options.checked);
+
+    let args = ["gen", "5", "--checked"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_gen() {
+    let args = ["gen", "12", "--seed", "3"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Gen {
+            day: 12,
+            seed: Some(3),
+            size: 10
+        }
+    );
+
+    let args = ["--size", "3"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_check() {
+    let args = ["check", "8", "--input", "d08.txt"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Check {
+            day: 8,
+            input: PathBuf::from("d08.txt")
+        }
+    );
+
+    let args = ["check", "8"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_scale() {
+    let args = ["scale", "14", "--factor", "3"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Scale {
+            day: 14,
+            input: None,
+            factor: 3
+        }
+    );
+
+    for args in [
+        &["scale", "14", "--factor", "0"][..],
+        &["run", "14", "--factor", "3"],
+    ] {
+        assert!(parse(args.iter().map(|s| s.to_string())).is_err());
+    }
+}
+
+#[test]
+fn test_parse_stream() {
+    let args = [
+        "stream", "12", "--part", "2", "--param", "copies=3", "--memory",
+    ];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Stream {
+            day: 12,
+            input: None,
+            parts: vec![2],
+            params: vec![("copies".to_string(), "3".to_string())]
+        }
+    );
+    assert!(options.memory);
+
+    for args in [&["stream"][..], &["stream", "12", "--no-cache"]] {
+        assert!(parse(args.iter().map(|s| s.to_string())).is_err());
+    }
+}
+
+#[test]
+fn test_parse_run() {
+    let args = ["run", "21", "--param", "steps=6", "--checked"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Run {
+            day: 21,
+            params: vec![("steps".to_string(), "6".to_string())]
+        }
+    );
+
+    let args = ["run", "21", "--param", "steps"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+    let args = ["--param", "steps=6"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_watch() {
+    let args = ["watch", "12", "--part", "2", "--param", "copies=3"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Watch {
+            day: 12,
+            input: None,
+            parts: vec![2],
+            params: vec![("copies".to_string(), "3".to_string())]
+        }
+    );
+
+    let args = ["run", "12", "--part", "2"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_example() {
+    let args = ["run", "11", "--example", "2"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(options.example, Some(2));
+    assert!(matches!(options.command, Command::Run { day: 11, .. }));
+
+    let args = ["run", "--example"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!((options.command, options.example), (Command::All, Some(1)));
+
+    for args in [&["run", "--example", "0"][..], &["gen", "11", "--example"]] {
+        assert!(parse(args.iter().map(|s| s.to_string())).is_err());
+    }
+}
+
+#[test]
+fn test_parse_explain() {
+    let args = ["run", "--explain", "json", "7"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(options.explain, Some(explain::Format::Json));
+    assert!(matches!(options.command, Command::Run { day: 7, .. }));
+
+    let args = ["run", "--explain", "13"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(options.explain, Some(explain::Format::Text));
+
+    for args in [&["run", "7", "--explain", "yaml"][..], &["--explain"]] {
+        assert!(parse(args.iter().map(|s| s.to_string())).is_err());
+    }
+}
+
+#[test]
+fn test_parse_cache() {
+    let options = parse(["--no-cache".to_string()].into_iter()).unwrap();
+    assert!(options.no_cache);
+
+    let args = ["cache", "clear"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(options.command, Command::ClearCache);
+
+    let args = ["cache", "clear", "--no-cache"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_timeout() {
+    let args = ["run", "25", "--timeout", "1.5"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(options.timeout, Some(Duration::from_millis(1500)));
+
+    for args in [
+        &["watch", "25", "--timeout", "2"][..],
+        &["repl", "--timeout", "2"],
+    ] {
+        let options = parse(args.iter().map(|s| s.to_string())).unwrap();
+        assert_eq!(options.timeout, Some(Duration::from_secs(2)));
+    }
+
+    for timeout in ["0", "-1", "soon"] {
+        let args = ["--timeout", timeout];
+        assert!(parse(args.into_iter().map(String::from)).is_err());
+    }
+    let args = ["gen", "25", "--timeout", "2"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_verbosity() {
+    let args = ["run", "22", "-vv"];
+    assert_eq!(
+        parse(args.into_iter().map(String::from)).unwrap().verbosity,
+        2
+    );
+    let args = ["-v", "-v", "-v"];
+    assert_eq!(
+        parse(args.into_iter().map(String::from)).unwrap().verbosity,
+        3
+    );
+}
+
+#[test]
+fn test_parse_memory() {
+    let args = ["run", "14", "--memory"];
+    assert!(parse(args.into_iter().map(String::from)).unwrap().memory);
+
+    let args = ["gen", "14", "--memory"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_serve() {
+    let args = ["serve", "--port", "9000", "--timeout", "10"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(options.command, Command::Serve { port: 9000 });
+
+    let options = parse(["serve".to_string()].into_iter()).unwrap();
+    assert_eq!(options.command, Command::Serve { port: 8023 });
+
+    let args = ["run", "1", "--port", "9000"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_bench() {
+    let args = ["bench", "14", "--runs", "3"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Bench {
+            day: Some(14),
+            runs: 3
+        }
+    );
+
+    let args = ["compare", "1", "2", "--threshold", "2.5"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Compare {
+            a: 1,
+            b: 2,
+            threshold: 2.5
+        }
+    );
+
+    for args in [
+        &["compare", "1"][..],
+        &["bench", "--threshold", "1"],
+        &["bench", "--runs", "0"],
+    ] {
+        assert!(parse(args.iter().map(|s| s.to_string())).is_err());
+    }
+}
+
+#[test]
+fn test_parse_report() {
+    let args = ["report", "--markdown", "--redact"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Report {
+            markdown: true,
+            redact: true
+        }
+    );
+
+    let args = ["run", "1", "--markdown"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
+
+#[test]
+fn test_parse_graph() {
+    let args = ["graph", "25", "--format", "graphml"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(
+        options.command,
+        Command::Graph {
+            day: 25,
+            input: None,
+            format: Format::GraphMl
+        }
+    );
+
+    for args in [
+        &["graph", "25", "--format", "svg"][..],
+        &["run", "25", "--format", "dot"],
+    ] {
+        assert!(parse(args.iter().map(|s| s.to_string())).is_err());
+    }
+}
+
+#[test]
+fn test_parse_repl() {
+    let args = ["repl", "--checked"];
+    let options = parse(args.into_iter().map(String::from)).unwrap();
+    assert_eq!(options.command, Command::Repl);
+
+    let args = ["repl", "--no-cache"];
+    assert!(parse(args.into_iter().map(String::from)).is_err());
+}
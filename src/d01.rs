@@ -1,9 +1,17 @@
-#[allow(dead_code)]
+use std::io::{self, BufRead};
+
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+};
+
 static TEST: &str = include_str!("../data/d01t");
-#[allow(dead_code)]
 static TEST2: &str = include_str!("../data/d01t2");
 #[allow(dead_code)]
-static INP: &str = include_str!("../data/d01");
+pub(crate) static INPUT: &str = include_str!("../data/d01");
+pub(crate) static TITLE: &str = "Trebuchet?!";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1]), Example::new(TEST2, &[2])];
 
 fn parse_input(inp: &str) -> Vec<&str> {
     inp.lines().collect()
@@ -29,40 +37,106 @@ fn parse_number(chars: &[char]) -> Option<u32> {
     }
 }
 
-pub(crate) fn get_solution_1() -> u32 {
-    let mut sum = 0;
-    for line in parse_input(INP) {
-        sum += line.chars().find_map(|c| c.to_digit(10)).unwrap() * 10
-            + line.chars().rev().find_map(|c| c.to_digit(10)).unwrap();
+// the first and the last digit of the line
+fn calibration_value(line: &str) -> u32 {
+    line.chars().find_map(|c| c.to_digit(10)).unwrap() * 10
+        + line.chars().rev().find_map(|c| c.to_digit(10)).unwrap()
+}
+
+// the first and the last digit of the line, spelled out or not
+fn calibration_value_spelled(line: &str) -> u32 {
+    let mut value = 0;
+    let len = line.len();
+    let chars = line.chars().collect::<Vec<_>>();
+    for i in 0..len {
+        if let Some(n) = parse_number(&chars[i..]) {
+            value += n * 10;
+            break;
+        }
+    }
+
+    for i in (0..len).rev() {
+        if let Some(n) = parse_number(&chars[i..]) {
+            value += n;
+            break;
+        }
     }
 
-    sum
+    value
 }
 
-pub(crate) fn get_solution_2() -> u32 {
-    let mut sum = 0;
-    for line in parse_input(INP) {
-        let len = line.len();
-        let chars = line.chars().collect::<Vec<_>>();
-        for i in 0..len {
-            if let Some(n) = parse_number(&chars[i..]) {
-                sum += n * 10;
-                break;
-            }
+pub(crate) fn get_solution_1(input: &str) -> u32 {
+    parse_input(input).into_iter().map(calibration_value).sum()
+}
+
+pub(crate) fn get_solution_2(input: &str) -> u32 {
+    parse_input(input)
+        .into_iter()
+        .map(calibration_value_spelled)
+        .sum()
+}
+
+pub(crate) fn stream_solution_1(reader: impl BufRead) -> io::Result<u32> {
+    reader
+        .lines()
+        .map(|line| line.map(|l| calibration_value(&l)))
+        .sum()
+}
+
+pub(crate) fn stream_solution_2(reader: impl BufRead) -> io::Result<u32> {
+    reader
+        .lines()
+        .map(|line| line.map(|l| calibration_value_spelled(&l)))
+        .sum()
+}
+
+/// Checks that every line consists of letters and digits and contains a digit, spelled out or not
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let digits = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        checker.chars(line, "abcdefghijklmnopqrstuvwxyz0123456789");
+        if !line.contains(|c: char| c.is_ascii_digit()) && !digits.iter().any(|d| line.contains(d))
+        {
+            checker.error(line, "line contains no digit");
         }
+    }
+    checker.finish()
+}
 
-        for i in (0..len).rev() {
-            if let Some(n) = parse_number(&chars[i..]) {
-                sum += n;
-                break;
+/// Generates `size` lines of letters, digits and spelled out digits
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let words = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        let mut line = String::new();
+        for _ in 0..rng.range(1, 6) {
+            match rng.range(0, 3) {
+                0 => line.push_str(rng.pick::<&str>(&words)),
+                1 => {
+                    let len = rng.range(1, 4);
+                    line.push_str(&rng.word(len))
+                }
+                _ => line.push((b'0' + rng.range(1, 10) as u8) as char),
             }
         }
+        // every line needs at least one digit
+        if !line.chars().any(|c| c.is_ascii_digit()) {
+            line.push((b'0' + rng.range(1, 10) as u8) as char);
+        }
+        inp.push_str(&line);
+        inp.push('\n');
     }
 
-    sum
+    inp
 }
 
 #[test]
 fn test_part2() {
-    assert_eq!(281, get_solution_2());
+    assert_eq!(281, get_solution_2(TEST2));
 }
@@ -1,8 +1,41 @@
-use std::ops::{Index, IndexMut};
+use std::{
+    io::{self, BufRead},
+    ops::{Index, IndexMut},
+};
+
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+    scale,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d02t");
-static INP: &str = include_str!("../data/d02");
+pub(crate) static INPUT: &str = include_str!("../data/d02");
+pub(crate) static TITLE: &str = "Cube Conundrum";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
+
+pub(crate) static PARAMS: &[Param] = &[
+    Param {
+        name: "red",
+        default: "12",
+        validate: params::count,
+        help: "red cubes in the bag",
+    },
+    Param {
+        name: "green",
+        default: "13",
+        validate: params::count,
+        help: "green cubes in the bag",
+    },
+    Param {
+        name: "blue",
+        default: "14",
+        validate: params::count,
+        help: "blue cubes in the bag",
+    },
+];
 
 type Game = Vec<Draw>;
 type Draw = [usize; 3];
@@ -48,25 +81,24 @@ impl IndexMut<Color> for [usize; 3] {
 }
 
 fn parse_input(inp: &str) -> Vec<Game> {
-    let mut games = Vec::new();
-
-    for line in inp.lines() {
-        let i = line.find(':').unwrap() + 2;
-        let mut game = Vec::new();
-
-        for draw in line[i..].split(';') {
-            let mut bag = [0, 0, 0];
-            for balls in draw.split(',') {
-                match balls.split_whitespace().collect::<Vec<_>>().as_slice() {
-                    &[n, color] => bag[Color::from(color)] = n.parse().unwrap(),
-                    _ => panic!("draw has wrong form"),
-                }
+    inp.lines().map(parse_game).collect()
+}
+
+fn parse_game(line: &str) -> Game {
+    let i = line.find(':').unwrap() + 2;
+    let mut game = Vec::new();
+
+    for draw in line[i..].split(';') {
+        let mut bag = [0, 0, 0];
+        for balls in draw.split(',') {
+            match balls.split_whitespace().collect::<Vec<_>>().as_slice() {
+                &[n, color] => bag[Color::from(color)] = n.parse().unwrap(),
+                _ => panic!("draw has wrong form"),
             }
-            game.push(bag);
         }
-        games.push(game);
+        game.push(bag);
     }
-    games
+    game
 }
 
 fn too_many(game: &Game, max: &Draw) -> bool {
@@ -76,9 +108,9 @@ fn too_many(game: &Game, max: &Draw) -> bool {
         .any(|draw| draw[C::R] > max[C::R] || draw[C::G] > max[C::G] || draw[C::B] > max[C::B])
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    let max = [12, 13, 14];
-    parse_input(INP)
+pub(crate) fn get_solution_1(input: &str, params: &Params) -> usize {
+    let max = ["red", "green", "blue"].map(|color| params.get::<usize>(color));
+    parse_input(input)
         .into_iter()
         .enumerate()
         .filter(|(_, g)| !too_many(g, &max))
@@ -106,8 +138,86 @@ fn determine_fewest(game: &Game) -> Draw {
     max
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    parse_input(INP).into_iter().fold(0, |s, g| {
+pub(crate) fn get_solution_2(input: &str) -> usize {
+    parse_input(input).into_iter().fold(0, |s, g| {
         s + determine_fewest(&g).into_iter().product::<usize>()
     })
 }
+
+pub(crate) fn stream_solution_1(reader: impl BufRead, params: &Params) -> io::Result<usize> {
+    let max = ["red", "green", "blue"].map(|color| params.get::<usize>(color));
+    let mut sum = 0;
+    for (i, line) in reader.lines().enumerate() {
+        if !too_many(&parse_game(&line?), &max) {
+            sum += i + 1;
+        }
+    }
+    Ok(sum)
+}
+
+pub(crate) fn stream_solution_2(reader: impl BufRead) -> io::Result<usize> {
+    reader
+        .lines()
+        .map(|line| {
+            line.map(|l| {
+                determine_fewest(&parse_game(&l))
+                    .into_iter()
+                    .product::<usize>()
+            })
+        })
+        .sum()
+}
+
+/// Checks that every line has the form `Game <id>: <count> <color>, ...; ...`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        let Some((game, draws)) = checker.split_once(line, ":") else {
+            continue;
+        };
+        match game.strip_prefix("Game ") {
+            Some(id) => _ = checker.parse::<usize>(id, "a game id"),
+            None => checker.error(game, "expected `Game <id>`"),
+        }
+
+        for balls in draws.split([';', ',']).map(|b| b.trim()) {
+            match balls.split_whitespace().collect::<Vec<_>>()[..] {
+                [n, color] => {
+                    checker.parse::<usize>(n, "a number of balls");
+                    if !["red", "green", "blue"].contains(&color) {
+                        checker.error(color, "expected `red`, `green` or `blue`");
+                    }
+                }
+                _ => checker.error(balls, "expected `<count> <color>`"),
+            }
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` games with up to six draws each
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut inp = String::new();
+
+    for id in 1..=size {
+        let draws: Vec<String> = (0..rng.range(1, 7))
+            .map(|_| {
+                let mut colors = ["red", "green", "blue"];
+                rng.shuffle(&mut colors);
+                colors[..rng.range(1, 4)]
+                    .iter()
+                    .map(|color| format!("{} {color}", rng.range(1, 21)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect();
+        inp.push_str(&format!("Game {id}: {}\n", draws.join("; ")));
+    }
+
+    inp
+}
+
+/// Repeats the games `factor` times with new ids
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    scale::renumber(inp, factor)
+}
@@ -1,10 +1,17 @@
 use std::collections::HashMap;
 
-#[allow(dead_code)]
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+};
+
 static TEST: &str = include_str!("../data/d03t");
 
 #[allow(dead_code)]
-static INP: &str = include_str!("../data/d03");
+pub(crate) static INPUT: &str = include_str!("../data/d03");
+pub(crate) static TITLE: &str = "Gear Ratios";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
 fn parse_input(input: &str) -> Vec<Vec<char>> {
     let mut map: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
@@ -72,8 +79,8 @@ fn parse_num(row: &[char], start: usize, width: usize) -> u32 {
         .unwrap()
 }
 
-pub(crate) fn get_solution_1() -> u32 {
-    let map = parse_input(INP);
+pub(crate) fn get_solution_1(input: &str) -> u32 {
+    let map = parse_input(input);
     let mut non_adj_nums = Vec::new();
 
     for (i, row) in map.iter().enumerate() {
@@ -97,8 +104,8 @@ pub(crate) fn get_solution_1() -> u32 {
     non_adj_nums.into_iter().sum()
 }
 
-pub(crate) fn get_solution_2() -> u32 {
-    let map = parse_input(INP);
+pub(crate) fn get_solution_2(input: &str) -> u32 {
+    let map = parse_input(input);
     let mut gears = HashMap::new();
     for (i, row) in map.iter().enumerate() {
         let mut j = 0;
@@ -124,3 +131,39 @@ pub(crate) fn get_solution_2() -> u32 {
         .map(|(_, nums)| nums.into_iter().product::<u32>())
         .sum()
 }
+
+/// Checks that the schematic is a rectangle of digits, periods and symbols
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    checker.grid(&lines, "0123456789.!\"#$%&'()*+,-/:;<=>?@[\\]^_`{|}~");
+    checker.finish()
+}
+
+/// Generates a `size` x `size` schematic
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let symbols = ['*', '#', '+', '$', '/', '=', '%', '@', '&', '-'];
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        let mut row = String::new();
+        while row.len() < size {
+            match rng.range(0, 10) {
+                0 => row.push(*rng.pick(&symbols)),
+                1 | 2 => {
+                    let num = rng.range(1, 1000).to_string();
+                    // numbers must not run into each other
+                    if row.len() + num.len() < size {
+                        row.push_str(&num);
+                        row.push('.');
+                    }
+                }
+                _ => row.push('.'),
+            }
+        }
+        inp.push_str(&row[..size]);
+        inp.push('\n');
+    }
+
+    inp
+}
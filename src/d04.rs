@@ -1,50 +1,205 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{self, BufRead},
+};
+
+use crate::days::Example;
+use crate::util::{
+    checked::Checked,
+    diag::{Checker, Diagnostic},
+    explain::Explanation,
+    rng::Rng,
+    scale,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d04t");
 #[allow(dead_code)]
-static INPUT: &str = include_str!("../data/d04");
+pub(crate) static INPUT: &str = include_str!("../data/d04");
+pub(crate) static TITLE: &str = "Scratchcards";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
 fn parse_input(input: &str) -> Vec<(HashSet<u32>, HashSet<u32>)> {
-    let mut cards = Vec::new();
-
-    for line in input.lines() {
-        let i = line.find(':').unwrap() + 1;
-        let card: Vec<_> = line[i..]
-            .split('|')
-            .map(|card| {
-                card.split_whitespace()
-                    .filter_map(|n| n.parse().ok())
-                    .collect::<HashSet<_>>()
-            })
-            .collect();
-        cards.push((card[0].clone(), card[1].clone()));
+    input.lines().map(parse_card).collect()
+}
+
+fn parse_card(line: &str) -> (HashSet<u32>, HashSet<u32>) {
+    let i = line.find(':').unwrap() + 1;
+    let card: Vec<_> = line[i..]
+        .split('|')
+        .map(|card| {
+            card.split_whitespace()
+                .filter_map(|n| n.parse().ok())
+                .collect::<HashSet<_>>()
+        })
+        .collect();
+    (card[0].clone(), card[1].clone())
+}
+
+// the number of winning numbers on a card
+fn matches((actual, win): (HashSet<u32>, HashSet<u32>)) -> usize {
+    actual.intersection(&win).count()
+}
+
+// the number of winning numbers on every card
+fn count_matches(cards: Vec<(HashSet<u32>, HashSet<u32>)>) -> Vec<usize> {
+    cards.into_iter().map(matches).collect()
+}
+
+fn points(matches: usize) -> usize {
+    match matches {
+        0 => 0,
+        n => 2_usize.pow(n as u32 - 1),
     }
+}
 
-    cards
+// the number of every card after winning the copies, the original included
+fn count_copies(matches: &[usize]) -> Vec<usize> {
+    let mut copies = vec![1; matches.len()];
+    for (i, n) in matches.iter().enumerate() {
+        for j in i + 1..i + n + 1 {
+            copies[j] = (Checked::new(copies[j], "count_copies") + copies[i]).get();
+        }
+    }
+    copies
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    parse_input(INPUT)
+pub(crate) fn get_solution_1(input: &str) -> usize {
+    count_matches(parse_input(input))
         .into_iter()
-        .map(|(actual, win)| actual.intersection(&win).count())
-        .filter(|n| n > &0)
-        .map(|n| 2_usize.pow(n as u32 - 1))
+        .map(points)
         .sum()
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    let cards = parse_input(INPUT);
-    let mut copies = vec![1; cards.len()];
-    cards
+pub(crate) fn get_solution_2(input: &str) -> usize {
+    count_copies(&count_matches(parse_input(input)))
         .into_iter()
-        .map(|(act, win)| act.intersection(&win).count())
-        .enumerate()
-        .map(|(i, n)| {
-            for j in i + 1..i + n + 1 {
-                copies[j] += copies[i];
-            }
-            copies[i]
-        })
         .sum()
 }
+
+pub(crate) fn stream_solution_1(reader: impl BufRead) -> io::Result<usize> {
+    reader
+        .lines()
+        .map(|line| line.map(|l| points(matches(parse_card(&l)))))
+        .sum()
+}
+
+// like `count_copies`, but only the copies won for the next cards are kept, which are at most as
+// many as there are winning numbers on a card
+pub(crate) fn stream_solution_2(reader: impl BufRead) -> io::Result<usize> {
+    let mut won = VecDeque::new();
+    let mut total = 0;
+    for line in reader.lines() {
+        let copies = 1 + won.pop_front().unwrap_or(0);
+        let n = matches(parse_card(&line?));
+        if won.len() < n {
+            won.resize(n, 0);
+        }
+        for next in won.iter_mut().take(n) {
+            *next = (Checked::new(*next, "stream_solution_2") + copies).get();
+        }
+        total = (Checked::new(total, "stream_solution_2") + copies).get();
+    }
+    Ok(total)
+}
+
+/// A row per card with its matches, and its points for part 1 or its copies for part 2
+pub(crate) fn explain(inp: &str, part: usize) -> Explanation {
+    let matches = count_matches(parse_input(inp));
+    let (mut explanation, counts) = match part {
+        1 => (
+            Explanation::new(
+                "the sum of the points of the cards, 2^(matches - 1) for a card with matches",
+                &["card", "matches", "points"],
+            ),
+            matches.iter().map(|&n| points(n)).collect(),
+        ),
+        _ => (
+            Explanation::new(
+                "the number of cards, every copy of a card wins a copy of the next `matches` cards",
+                &["card", "matches", "copies"],
+            ),
+            count_copies(&matches),
+        ),
+    };
+    for (i, (n, count)) in matches.into_iter().zip(counts).enumerate() {
+        explanation.push(vec![(i + 1).into(), n.into(), count.into()]);
+    }
+    explanation
+}
+
+/// Checks that every line has the form `Card <id>: <numbers> | <numbers>`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        let Some((card, numbers)) = checker.split_once(line, ":") else {
+            continue;
+        };
+        match card.strip_prefix("Card") {
+            Some(id) => _ = checker.parse::<usize>(id.trim(), "a card id"),
+            None => checker.error(card, "expected `Card <id>`"),
+        }
+
+        let Some((winning, drawn)) = checker.split_once(numbers, "|") else {
+            continue;
+        };
+        for n in winning.split_whitespace().chain(drawn.split_whitespace()) {
+            checker.parse::<u32>(n, "a number");
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` cards, with five winning and eight drawn numbers each
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut inp = String::new();
+
+    for id in 1..=size {
+        let mut numbers: Vec<usize> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+        let (winning, rest) = numbers.split_at(5);
+
+        // cards can't win more cards than there are left
+        let n_matches = rng.range(0, 6).min(size - id);
+        let mut drawn: Vec<usize> = winning[..n_matches]
+            .iter()
+            .chain(&rest[..8 - n_matches])
+            .copied()
+            .collect();
+        rng.shuffle(&mut drawn);
+
+        let fmt = |nums: &[usize]| {
+            nums.iter()
+                .map(|n| format!("{n:2}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        inp.push_str(&format!(
+            "Card {id:3}: {} | {}\n",
+            fmt(winning),
+            fmt(&drawn)
+        ));
+    }
+
+    inp
+}
+
+/// Repeats the cards `factor` times with new numbers, the cards still only win copies of cards
+/// which exist
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    scale::renumber(inp, factor)
+}
+
+#[test]
+fn test_copies_overflow() {
+    use crate::util::checked;
+
+    // every card wins a copy of the next two, so the copies grow like the Fibonacci numbers
+    let inp: String = (1..=100)
+        .map(|i| match i {
+            99.. => format!("Card {i}: 1 2 | 3 4\n"),
+            _ => format!("Card {i}: 1 2 | 1 2\n"),
+        })
+        .collect();
+    assert!(checked::catch_checked(|| get_solution_2(&inp)).is_err());
+    assert!(checked::catch_checked(|| stream_solution_2(inp.as_bytes())).is_err());
+}
@@ -1,10 +1,19 @@
-#[allow(dead_code)]
+use crate::days::Example;
+use crate::util::{
+    checked::Checked,
+    diag::{Checker, Diagnostic},
+    log::trace,
+    rng::Rng,
+};
+
 static TEST: &str = include_str!("../data/d05t");
 
 #[allow(dead_code)]
-static INPUT: &str = include_str!("../data/d05");
+pub(crate) static INPUT: &str = include_str!("../data/d05");
+pub(crate) static TITLE: &str = "If You Give A Seed A Fertilizer";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct MapEntry {
     dest: usize,
     source: usize,
@@ -18,7 +27,10 @@ impl MapEntry {
 
     fn try_convert(&self, item: usize) -> Option<isize> {
         if self.in_range(item) {
-            Some(item as isize + (self.dest as isize - self.source as isize))
+            let item = Checked::new(item, "try_convert").cast::<isize>();
+            let dest = Checked::new(self.dest, "try_convert").cast::<isize>();
+            let source = Checked::new(self.source, "try_convert").cast::<isize>();
+            Some((item + (dest - source)).get())
         } else {
             None
         }
@@ -53,6 +65,14 @@ impl MapEntry {
             if end > mend {
                 todo.push((mend.max(start), end));
             }
+            trace!(
+                "d05",
+                "{start}..{end} split by {}..{}: mapped {:?}, left {:?}",
+                self.source,
+                self.source + self.range,
+                (mstart < mend).then(|| mapped.last().unwrap()),
+                &todo
+            );
         }
 
         todo
@@ -74,20 +94,18 @@ impl From<&[usize]> for MapEntry {
 
 fn parse_input(input: &str) -> (Vec<usize>, Vec<Vec<MapEntry>>) {
     let mut maps = Vec::new();
-    let mut lines = input.lines().collect::<Vec<_>>();
-    let seed_lines = lines.remove(0);
+    let mut lines = input.lines();
+    let seed_lines = lines.next().unwrap();
     let seeds = seed_lines[seed_lines.find(':').unwrap() + 1..]
         .split_whitespace()
         .filter_map(|s| s.parse::<usize>().ok())
         .collect::<Vec<_>>();
-    lines.remove(0); // empty line
+    lines.next(); // empty line
 
-    while !lines.is_empty() {
-        // skip mapping line
-        lines.remove(0);
+    // skip mapping line
+    while lines.next().is_some() {
         let mut map = Vec::new();
-        while !lines.is_empty() {
-            let line = lines.remove(0);
+        for line in lines.by_ref() {
             if line.is_empty() {
                 break;
             } else {
@@ -107,13 +125,19 @@ fn parse_input(input: &str) -> (Vec<usize>, Vec<Vec<MapEntry>>) {
     (seeds, maps)
 }
 
+/// The seeds and the maps in the order they are applied
+pub(crate) fn show(inp: &str) -> String {
+    let (seeds, maps) = parse_input(inp);
+    format!("seeds: {seeds:?}\nmaps: {maps:#?}")
+}
+
 // assumes we always start with Type::Seed
 fn map_to_location(mut seed: usize, maps: &[Vec<MapEntry>]) -> usize {
     for map in maps {
         let mut next = seed;
         for entry in map {
             if let Some(n) = entry.try_convert(seed) {
-                next = n as usize;
+                next = Checked::new(n, "map_to_location").cast::<usize>().get();
                 break;
             }
         }
@@ -136,8 +160,8 @@ fn map_to_location_range(
     seeds
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    let (seeds, maps) = parse_input(INPUT);
+pub(crate) fn get_solution_1(input: &str) -> usize {
+    let (seeds, maps) = parse_input(input);
 
     seeds
         .into_iter()
@@ -146,8 +170,8 @@ pub(crate) fn get_solution_1() -> usize {
         .unwrap_or(usize::MAX)
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    let (seeds, maps) = parse_input(INPUT);
+pub(crate) fn get_solution_2(input: &str) -> usize {
+    let (seeds, maps) = parse_input(input);
     let mut seeds: Vec<(usize, usize)> = seeds
         .windows(2)
         .step_by(2)
@@ -162,14 +186,115 @@ pub(crate) fn get_solution_2() -> usize {
         .0
 }
 
+/// Checks that the input starts with an even number of seeds, followed by maps with entries of
+/// three numbers each
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let mut blocks = checker.blocks().into_iter();
+
+    if let Some(seeds) = blocks.next().and_then(|b| b.first().copied()) {
+        match seeds.strip_prefix("seeds:") {
+            Some(numbers) => {
+                for n in numbers.split_whitespace() {
+                    checker.parse::<usize>(n, "a seed");
+                }
+                if numbers.split_whitespace().count() % 2 != 0 {
+                    checker.error_after(seeds, "expected an even number of seeds");
+                }
+            }
+            None => checker.error(seeds, "expected `seeds:`"),
+        }
+    }
+
+    for block in blocks {
+        let Some((header, entries)) = block.split_first() else {
+            continue;
+        };
+        if !header.ends_with(" map:") {
+            checker.error(header, "expected `<source>-to-<destination> map:`");
+        }
+        for entry in entries {
+            let numbers: Vec<_> = entry.split_whitespace().collect();
+            if numbers.len() != 3 {
+                checker.error(entry, "expected `<destination> <source> <length>`");
+            }
+            for n in numbers {
+                checker.parse::<usize>(n, "a number");
+            }
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` seed ranges and maps with `size` entries each, entries of the same map may
+/// overlap
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let names = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let max = 1 << 32;
+    let size = size.max(1);
+
+    let seeds = (0..size)
+        .map(|_| format!("{} {}", rng.range(0, max / 2), rng.range(1, max / 8)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut inp = format!("seeds: {seeds}\n");
+
+    for names in names.windows(2) {
+        inp.push_str(&format!("\n{}-to-{} map:\n", names[0], names[1]));
+        for _ in 0..size {
+            let range = rng.range(1, max / 4);
+            let dest = rng.range(0, max - range);
+            let source = rng.range(0, max - range);
+            inp.push_str(&format!("{dest} {source} {range}\n"));
+        }
+    }
+
+    inp
+}
+
+/// Turns every range of seeds into `factor` ranges of the same length one after another, the
+/// maps stay the same
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    let (seeds, maps) = inp.split_once('\n').ok_or("there are no maps")?;
+    let seeds = seeds
+        .strip_prefix("seeds:")
+        .ok_or("the first line has no seeds")?
+        .split_whitespace()
+        .map(|s| s.parse::<usize>().map_err(|_| format!("invalid seed: {s}")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut scaled = Vec::new();
+    for range in seeds.chunks(2) {
+        match *range {
+            [start, len] => {
+                for i in 0..factor {
+                    scaled.extend([start + i * len, len]);
+                }
+            }
+            _ => scaled.extend(range),
+        }
+    }
+    let scaled: Vec<_> = scaled.iter().map(|s| s.to_string()).collect();
+    Ok(format!("seeds: {}\n{maps}", scaled.join(" ")))
+}
+
 #[test]
 fn test_solution_1() {
-    println!("{}", get_solution_1());
+    println!("{}", get_solution_1(INPUT));
 }
 
 #[test]
 fn test_solution_2() {
-    println!("{}", get_solution_2());
+    println!("{}", get_solution_2(INPUT));
 }
 
 #[test]
@@ -260,3 +385,58 @@ fn test_overlapping_center() {
     assert!(actual.is_empty());
     assert_eq!(mapped_seeds, vec![(21, 25)]);
 }
+
+#[test]
+fn test_map_to_location_range_matches_brute_force() {
+    use crate::util::prop::{self, shrink_each, shrink_vec};
+    use std::collections::BTreeSet;
+
+    prop::check(
+        "map_to_location_range",
+        |rng, size| {
+            let seeds: Vec<(usize, usize)> = (0..rng.range(1, 4))
+                .map(|_| {
+                    let start = rng.range(0, 50);
+                    (start, start + rng.range(1, 10))
+                })
+                .collect();
+            let maps: Vec<Vec<MapEntry>> = (0..rng.range(1, 4))
+                .map(|_| {
+                    (0..rng.range(1, size.min(6) + 1))
+                        .map(|_| MapEntry {
+                            dest: rng.range(0, 50),
+                            source: rng.range(0, 50),
+                            range: rng.range(1, 20),
+                        })
+                        .collect()
+                })
+                .collect();
+            (seeds, maps)
+        },
+        |(seeds, maps)| {
+            let mut smaller: Vec<_> = shrink_vec(seeds)
+                .into_iter()
+                .map(|seeds| (seeds, maps.clone()))
+                .collect();
+            smaller.extend(
+                shrink_vec(maps)
+                    .into_iter()
+                    .chain(shrink_each(maps, |map| shrink_vec(map)))
+                    .map(|maps| (seeds.clone(), maps)),
+            );
+            smaller
+        },
+        |(seeds, maps)| {
+            let expected: BTreeSet<usize> = seeds
+                .iter()
+                .flat_map(|(start, end)| *start..*end)
+                .map(|seed| map_to_location(seed, maps))
+                .collect();
+            let actual: BTreeSet<usize> = map_to_location_range(seeds.clone(), maps)
+                .into_iter()
+                .flat_map(|(start, end)| start..end)
+                .collect();
+            prop::assert_eq(actual, expected)
+        },
+    );
+}
@@ -1,3 +1,5 @@
+pub(crate) static TITLE: &str = "Wait For It";
+
 #[allow(dead_code)]
 static TEST: [(usize, usize); 3] = [(7, 9), (15, 40), (30, 200)];
 
@@ -1,21 +1,44 @@
-use std::{cmp::Ordering, fmt::Display};
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    fmt::Display,
+    io::{self, BufRead},
+};
+
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    explain::Explanation,
+    hash::FastMap,
+    log::trace,
+    rng::Rng,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d07t");
-static INPUT: &str = include_str!("../data/d07");
+pub(crate) static INPUT: &str = include_str!("../data/d07");
+pub(crate) static TITLE: &str = "Camel Cards";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
 fn parse_input(input: &str) -> Vec<(Hand, usize)> {
-    let mut bets = Vec::new();
-    for line in input.lines() {
-        let parts = line.split_whitespace().collect::<Vec<&str>>();
-        bets.push((parts[0].into(), parts[1].parse::<usize>().unwrap()));
-    }
+    input.lines().map(parse_bid).collect()
+}
 
-    bets
+fn parse_bid(line: &str) -> (Hand, usize) {
+    let parts = line.split_whitespace().collect::<Vec<&str>>();
+    (parts[0].into(), parts[1].parse::<usize>().unwrap())
 }
 
-fn determine_total_winnings(mut bids: Vec<(Hand, usize)>) -> usize {
+// sorts the bids from the weakest to the strongest hand
+fn rank(bids: &mut [(Hand, usize)]) {
+    // once per hand, the comparisons of the sort are too many to log
+    for (hand, _) in bids.iter() {
+        trace!("d07", "{hand} has strength {}", hand.strength());
+    }
     bids.sort_by(|(lhs, _), (rhs, _)| lhs.compare(rhs));
+}
+
+fn determine_total_winnings(mut bids: Vec<(Hand, usize)>) -> usize {
+    rank(&mut bids);
 
     bids.into_iter()
         .enumerate()
@@ -23,20 +46,99 @@ fn determine_total_winnings(mut bids: Vec<(Hand, usize)>) -> usize {
         .sum()
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    let bids = parse_input(INPUT);
+pub(crate) fn get_solution_1(input: &str) -> usize {
+    let bids = parse_input(input);
     determine_total_winnings(bids)
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    let mut bids = parse_input(INPUT);
+pub(crate) fn get_solution_2(input: &str) -> usize {
+    let mut bids = parse_input(input);
     for (hand, _) in &mut bids {
         hand.p2 = true;
     }
     determine_total_winnings(bids)
 }
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+/// The bids on equal hands, which are ranked in the order of the input like by `rank`
+#[derive(Default)]
+struct Tally {
+    count: usize,
+    bids: usize,
+    /// the bids times their position among the equal hands
+    weighted: usize,
+}
+
+// the hands can't be ranked before all of them are read, but there are at most 13^5 different
+// ones, so only a tally per hand is kept instead of the bids
+fn stream_total_winnings(reader: impl BufRead, p2: bool) -> io::Result<usize> {
+    let mut tallies: FastMap<[Card; 5], Tally> = FastMap::default();
+    for line in reader.lines() {
+        let (hand, bid) = parse_bid(&line?);
+        let tally = tallies.entry(hand.cards).or_default();
+        tally.count += 1;
+        tally.bids += bid;
+        tally.weighted += bid * tally.count;
+    }
+
+    let mut hands: Vec<_> = tallies
+        .into_iter()
+        .map(|(cards, tally)| (Hand { cards, p2 }, tally))
+        .collect();
+    hands.sort_by(|(lhs, _), (rhs, _)| lhs.compare(rhs));
+
+    let mut ranked = 0;
+    let mut total = 0;
+    for (_, tally) in hands {
+        total += ranked * tally.bids + tally.weighted;
+        ranked += tally.count;
+    }
+    Ok(total)
+}
+
+pub(crate) fn stream_solution_1(reader: impl BufRead) -> io::Result<usize> {
+    stream_total_winnings(reader, false)
+}
+
+pub(crate) fn stream_solution_2(reader: impl BufRead) -> io::Result<usize> {
+    stream_total_winnings(reader, true)
+}
+
+/// A row per hand from the weakest to the strongest, with jokers for part 2
+pub(crate) fn explain(inp: &str, part: usize) -> Explanation {
+    let mut bids = parse_input(inp);
+    for (hand, _) in &mut bids {
+        hand.p2 = part == 2;
+    }
+    rank(&mut bids);
+
+    let mut explanation = Explanation::new(
+        "the sum of the winnings of the hands, their bid times their rank",
+        &["rank", "hand", "type", "bid", "winnings"],
+    );
+    for (i, (hand, bid)) in bids.iter().enumerate() {
+        explanation.push(vec![
+            (i + 1).into(),
+            hand.to_string().into(),
+            TYPES[hand.strength()].into(),
+            (*bid).into(),
+            (bid * (i + 1)).into(),
+        ]);
+    }
+    explanation
+}
+
+/// The names of the types of hands by their score
+static TYPES: [&str; 7] = [
+    "high card",
+    "one pair",
+    "two pair",
+    "three of a kind",
+    "full house",
+    "four of a kind",
+    "five of a kind",
+];
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
 enum Card {
     Two = 2,
     Three = 3,
@@ -150,24 +252,20 @@ impl From<&str> for Hand {
 
 impl Hand {
     fn compare(&self, other: &Self) -> Ordering {
-        let lhs = if self.p2 {
-            self.score_joker()
-        } else {
-            self.score()
-        };
-
-        let rhs = if other.p2 {
-            other.score_joker()
-        } else {
-            other.score()
-        };
-
-        match lhs.cmp(&rhs) {
+        match self.strength().cmp(&other.strength()) {
             Ordering::Equal => self.compare_strongest_card(other),
             ordering => ordering,
         }
     }
 
+    // the score of the type of the hand, with jokers for part 2
+    fn strength(&self) -> usize {
+        match self.p2 {
+            true => self.score_joker(),
+            false => self.score(),
+        }
+    }
+
     fn compare_strongest_card(&self, other: &Self) -> Ordering {
         let p2 = self.p2;
         for (lhs, rhs) in self.cards.iter().zip(other.cards) {
@@ -285,6 +383,77 @@ impl Hand {
     }
 }
 
+/// Checks that every line has the form `<hand> <bid>`, with hands of five cards
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [hand, bid] => {
+                checker.chars(hand, "23456789TJQKA");
+                if hand.len() != 5 {
+                    checker.error(hand, format!("expected 5 cards, found {}", hand.len()));
+                }
+                checker.parse::<usize>(bid, "a bid");
+            }
+            _ => checker.error(line, "expected `<hand> <bid>`"),
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` hands and their bids
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let cards = [
+        '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+    ];
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        // draw from a smaller pool of cards now and then, to get more pairs and such
+        let pool = &cards[rng.range(0, 10)..];
+        let hand: String = (0..5).map(|_| *rng.pick(pool)).collect();
+        inp.push_str(&format!("{hand} {}\n", rng.range(1, 1001)));
+    }
+
+    inp
+}
+
+/// Repeats the hands `factor` times, the copies have their cards relabelled until they are
+/// unique, which keeps their type without jokers
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    const LABELS: &[u8] = b"23456789TJQKA";
+    let relabel = |hand: &str, shift: usize| -> String {
+        hand.bytes()
+            .map(|c| {
+                let i = LABELS.iter().position(|&l| l == c).unwrap();
+                LABELS[(i + shift) % LABELS.len()] as char
+            })
+            .collect()
+    };
+
+    let bids = inp
+        .lines()
+        .map(|line| match line.split_once(' ') {
+            Some((hand, bid)) if hand.bytes().all(|c| LABELS.contains(&c)) => Ok((hand, bid)),
+            _ => Err(format!("invalid hand: {line}")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut seen: HashSet<String> = bids.iter().map(|(hand, _)| hand.to_string()).collect();
+
+    let mut scaled = inp.to_string();
+    for copy in 1..factor {
+        for (hand, bid) in &bids {
+            let hand = (copy..copy + LABELS.len())
+                .map(|shift| relabel(hand, shift))
+                .find(|h| !seen.contains(h))
+                .ok_or_else(|| format!("there are too few distinct hands for {factor} copies"))?;
+            scaled.push_str(&format!("{hand} {bid}\n"));
+            seen.insert(hand);
+        }
+    }
+    Ok(scaled)
+}
+
 #[test]
 fn test_is_n_1() {
     let hand: Hand = "A2345".into();
@@ -402,5 +571,29 @@ fn test_score_joker_full_house() {
 
 #[test]
 fn test_solution_p2() {
-    println!("{}", get_solution_1());
+    println!("{}", get_solution_1(INPUT));
+}
+
+#[test]
+fn test_explain() {
+    use crate::util::explain::Format;
+
+    let explanation = explain(TEST, 2).write(Format::Json);
+    assert!(explanation.contains(
+        r#"{"rank":5,"hand":"KTJJT","type":"four of a kind","bid":220,"winnings":1100}"#
+    ));
+}
+
+#[test]
+fn test_stream_equal_hands() {
+    // equal hands are ranked in the order of the input, like by the sort in `rank`
+    let inp = "32T3K 765\nKK677 28\n32T3K 684\nKTJJT 220\n32T3K 7\n";
+    assert_eq!(
+        stream_solution_1(inp.as_bytes()).unwrap(),
+        get_solution_1(inp)
+    );
+    assert_eq!(
+        stream_solution_2(inp.as_bytes()).unwrap(),
+        get_solution_2(inp)
+    );
 }
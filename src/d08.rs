@@ -1,12 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::days::Example;
+use crate::util::{
+    bigint::BigUint,
+    cancel,
+    diag::{Checker, Diagnostic},
+    graph::{Format, Graph},
+    rng::Rng,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d08t");
-#[allow(dead_code)]
 static TEST2: &str = include_str!("../data/d08t2");
-static INPUT: &str = include_str!("../data/d08");
+pub(crate) static INPUT: &str = include_str!("../data/d08");
+pub(crate) static TITLE: &str = "Haunted Wasteland";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1]), Example::new(TEST2, &[2])];
 
-type Network = HashMap<&'static str, (&'static str, &'static str)>;
+type Network<'a> = HashMap<&'a str, (&'a str, &'a str)>;
 
 #[derive(Debug)]
 enum Turn {
@@ -23,7 +32,7 @@ impl From<char> for Turn {
     }
 }
 
-fn parse_input(input: &'static str) -> (Vec<Turn>, Network) {
+fn parse_input(input: &str) -> (Vec<Turn>, Network<'_>) {
     let mut line_iter = input.lines();
     // parse turns
     let turns = line_iter
@@ -40,21 +49,36 @@ fn parse_input(input: &'static str) -> (Vec<Turn>, Network) {
     (turns, network)
 }
 
-pub fn get_solution_1() -> usize {
-    let (turns, network) = parse_input(INPUT);
+/// The network as a graph, with the turn along every edge
+pub(crate) fn export_graph(inp: &str, format: Format) -> String {
+    let (_, network) = parse_input(inp);
+    let mut nodes: Vec<_> = network.into_iter().collect();
+    nodes.sort_unstable();
+
+    let mut graph = Graph::directed();
+    for (node, (left, right)) in nodes {
+        graph.add_edge(node, left, 'L');
+        graph.add_edge(node, right, 'R');
+    }
+    graph.write(format)
+}
+
+pub fn get_solution_1(input: &str) -> usize {
+    let (turns, network) = parse_input(input);
     traverse(turns, network)
 }
 
-pub fn get_solution_2() -> usize {
-    let (turns, network) = parse_input(INPUT);
+pub fn get_solution_2(input: &str) -> BigUint {
+    let (turns, network) = parse_input(input);
     traverse_ghost(turns, network)
 }
 
-fn traverse(turns: Vec<Turn>, network: Network) -> usize {
+fn traverse(turns: Vec<Turn>, network: Network<'_>) -> usize {
     let mut current = "AAA";
     let goal = "ZZZ";
     assert!(!turns.is_empty());
     for (step, turn) in turns.iter().cycle().enumerate() {
+        cancel::check();
         current = match turn {
             Turn::Left => network.get(current).unwrap().0,
             Turn::Right => network.get(current).unwrap().1,
@@ -67,7 +91,7 @@ fn traverse(turns: Vec<Turn>, network: Network) -> usize {
     unreachable!();
 }
 
-fn traverse_ghost(turns: Vec<Turn>, network: Network) -> usize {
+fn traverse_ghost(turns: Vec<Turn>, network: Network<'_>) -> BigUint {
     // get all nodes that end in 'A'
     let mut currents: Vec<&str> = network
         .keys()
@@ -78,6 +102,7 @@ fn traverse_ghost(turns: Vec<Turn>, network: Network) -> usize {
     let mut steps = Vec::new();
 
     for (step, turn) in turns.iter().cycle().enumerate() {
+        cancel::check();
         currents = currents
             .into_iter()
             .map(|n| match turn {
@@ -102,21 +127,122 @@ fn traverse_ghost(turns: Vec<Turn>, network: Network) -> usize {
     lcm(&steps)
 }
 
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    if a < b {
-        std::mem::swap(&mut a, &mut b);
+fn lcm(numbers: &[usize]) -> BigUint {
+    numbers
+        .iter()
+        .map(|&n| BigUint::from(n))
+        .reduce(|acc, n| &acc * &n / acc.gcd(&n))
+        .unwrap_or_default()
+}
+
+/// Checks that the input has a line of turns, followed by nodes of the form
+/// `AAA = (BBB, CCC)` which only point to defined nodes
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    let Some((turns, lines)) = lines.split_first() else {
+        return checker.finish();
+    };
+    checker.chars(turns, "LR");
+    match lines.split_first() {
+        Some((empty, _)) if !empty.is_empty() => {
+            checker.error(empty, "expected an empty line after the turns")
+        }
+        _ => (),
+    }
+
+    let mut nodes = HashSet::new();
+    let mut targets = Vec::new();
+    for line in lines.iter().skip(1) {
+        let Some((node, children)) = checker.split_once(line, " = ") else {
+            continue;
+        };
+        let Some(children) = children.strip_prefix('(').and_then(|c| c.strip_suffix(')')) else {
+            checker.error(children, "expected `(<left>, <right>)`");
+            continue;
+        };
+        let Some((left, right)) = checker.split_once(children, ", ") else {
+            continue;
+        };
+
+        for name in [node, left, right] {
+            if name.len() != 3 || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+                checker.error(name, "expected a node name of three letters or digits");
+            }
+        }
+        nodes.insert(node);
+        targets.extend([left, right]);
     }
 
-    match b == 0 {
-        true => a,
-        false => gcd(b, a % b),
+    for target in targets.into_iter().filter(|t| !nodes.contains(t)) {
+        checker.error(target, format!("unknown node `{target}`"));
     }
+    checker.finish()
 }
 
-fn lcm(numbers: &[usize]) -> usize {
-    numbers
-        .iter()
-        .copied()
-        .reduce(|acc, n| acc * n / gcd(acc, n))
-        .unwrap_or(0)
+/// Generates a network of roughly `size` nodes.
+///
+/// Each ghost walks a chain of nodes from its start to its goal, which then leads back into the
+/// chain. Every node of a chain leads either directly to the next node, or takes a detour over an
+/// extra node.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut names = HashSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut name = |rng: &mut Rng, last: &[u8]| loop {
+        let name = format!("{}{}", rng.word(2).to_uppercase(), *rng.pick(last) as char);
+        if names.insert(name.clone()) {
+            break name;
+        }
+    };
+    let middle: Vec<u8> = (b'B'..b'Z').collect();
+
+    let n_ghosts = (size / 16).clamp(1, 6);
+    let chain_len = (size / n_ghosts / 2).max(2);
+    let mut nodes = Vec::new();
+
+    for ghost in 0..n_ghosts {
+        let mut chain = match ghost {
+            0 => vec!["AAA".to_string()],
+            _ => vec![name(rng, b"A")],
+        };
+        chain.extend((1..chain_len).map(|_| name(rng, &middle)));
+        chain.push(match ghost {
+            0 => "ZZZ".to_string(),
+            _ => name(rng, b"Z"),
+        });
+
+        for w in chain.windows(2) {
+            let (direct, detour) = match rng.chance(50) {
+                true => (w[1].clone(), w[1].clone()),
+                false => (w[1].clone(), name(rng, &middle)),
+            };
+            if direct != detour {
+                nodes.push(format!("{detour} = ({}, {})", w[1], w[1]));
+            }
+            match rng.chance(50) {
+                true => nodes.push(format!("{} = ({direct}, {detour})", w[0])),
+                false => nodes.push(format!("{} = ({detour}, {direct})", w[0])),
+            }
+        }
+        // the goal leads back into the chain
+        nodes.push(format!(
+            "{} = ({}, {})",
+            chain[chain_len], chain[1], chain[1]
+        ));
+    }
+    rng.shuffle(&mut nodes);
+
+    let turns: String = (0..rng.range(1, size.max(2)))
+        .map(|_| *rng.pick(&['L', 'R']))
+        .collect();
+
+    format!("{turns}\n\n{}\n", nodes.join("\n"))
+}
+
+#[test]
+fn test_unreachable_goal_is_cancelled() {
+    use std::time::Duration;
+
+    let inp = "LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)\n";
+    let result = cancel::with_timeout(Duration::from_millis(50), move || get_solution_1(inp));
+    assert_eq!(result, None);
 }
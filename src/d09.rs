@@ -1,13 +1,22 @@
-#[allow(dead_code)]
+use std::io::{self, BufRead};
+
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+};
+
 static TEST: &str = include_str!("../data/d09t");
-static INPUT: &str = include_str!("../data/d09");
+pub(crate) static INPUT: &str = include_str!("../data/d09");
+pub(crate) static TITLE: &str = "Mirage Maintenance";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
-pub fn get_solution_1() -> isize {
-    parse_input(INPUT).iter_mut().map(|n| derive(n)).sum()
+pub fn get_solution_1(input: &str) -> isize {
+    parse_input(input).iter_mut().map(|n| derive(n)).sum()
 }
 
-pub fn get_solution_2() -> isize {
-    parse_input(INPUT)
+pub fn get_solution_2(input: &str) -> isize {
+    parse_input(input)
         .iter_mut()
         .map(|n| {
             n.reverse();
@@ -16,14 +25,33 @@ pub fn get_solution_2() -> isize {
         .sum()
 }
 
-fn parse_input(input: &str) -> Vec<Vec<isize>> {
-    input
+pub(crate) fn stream_solution_1(reader: impl BufRead) -> io::Result<isize> {
+    reader
+        .lines()
+        .map(|line| line.map(|l| derive(&parse_line(&l))))
+        .sum()
+}
+
+pub(crate) fn stream_solution_2(reader: impl BufRead) -> io::Result<isize> {
+    reader
         .lines()
         .map(|line| {
-            line.split_whitespace()
-                .filter_map(|n| str::parse::<isize>(n).ok())
-                .collect()
+            line.map(|l| {
+                let mut nums = parse_line(&l);
+                nums.reverse();
+                derive(&nums)
+            })
         })
+        .sum()
+}
+
+fn parse_input(input: &str) -> Vec<Vec<isize>> {
+    input.lines().map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Vec<isize> {
+    line.split_whitespace()
+        .filter_map(|n| str::parse::<isize>(n).ok())
         .collect()
 }
 
@@ -34,3 +62,41 @@ fn derive(nums: &[isize]) -> isize {
     let deltas = &nums.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
     derive(deltas) + nums.last().unwrap()
 }
+
+/// Checks that every line is a sequence of numbers
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        if line.trim().is_empty() {
+            checker.error(line, "expected a sequence of numbers");
+        }
+        for n in line.split_whitespace() {
+            checker.parse::<isize>(n, "a number");
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` sequences of 21 values of polynomials with a degree up to five
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        let coefficients: Vec<isize> = (0..rng.range(1, 7))
+            .map(|_| rng.range(0, 21) as isize - 10)
+            .collect();
+        let values: Vec<String> = (0..21)
+            .map(|x| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, c| acc * x + c)
+                    .to_string()
+            })
+            .collect();
+        inp.push_str(&values.join(" "));
+        inp.push('\n');
+    }
+
+    inp
+}
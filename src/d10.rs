@@ -1,15 +1,26 @@
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::ops::Index;
 
-#[allow(dead_code)]
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+    scale,
+};
+
 static TEST: &str = include_str!("../data/d10t");
-#[allow(dead_code)]
 static TEST2: &str = include_str!("../data/d10t2");
-#[allow(dead_code)]
 static TEST3: &str = include_str!("../data/d10t3");
-#[allow(dead_code)]
 static TEST4: &str = include_str!("../data/d10t4");
-static INPUT: &str = include_str!("../data/d10");
+pub(crate) static INPUT: &str = include_str!("../data/d10");
+pub(crate) static TITLE: &str = "Pipe Maze";
+pub(crate) static EXAMPLES: &[Example] = &[
+    Example::new(TEST, &[1]),
+    Example::new(TEST2, &[2]),
+    Example::new(TEST3, &[2]),
+    Example::new(TEST4, &[2]),
+];
 
 type Position = (usize, usize);
 
@@ -72,9 +83,11 @@ impl Map {
     }
 
     fn can_move(&self, pos: Position, dir: Dir) -> bool {
-        if !self.is_valid_direction(pos, dir) {
-            return false;
-        }
+        self.is_valid_direction(pos, dir) && self.connects(pos, dir)
+    }
+
+    // whether the tile next to `pos` in `dir` has a pipe leading back to `pos`
+    fn connects(&self, pos: Position, dir: Dir) -> bool {
         match dir {
             Dir::North => matches!(
                 self[(pos.0, pos.1.overflowing_sub(1).0)],
@@ -107,21 +120,16 @@ impl Map {
     }
 
     fn determine_start_tile(&self, start: Position) -> Tile {
-        // check if we can go south, east left or right
-        if self.can_move(start, Dir::North) && self.can_move(start, Dir::East) {
-            // all norths
-            Tile::NorthEast
-        } else if self.can_move(start, Dir::North) && self.can_move(start, Dir::South) {
-            Tile::Vertical
-        } else if self.can_move(start, Dir::North) && self.can_move(start, Dir::West) {
-            Tile::NorthWest
-        } else if self.can_move(start, Dir::East) && self.can_move(start, Dir::South) {
-            // all easts
-            Tile::SouthEast
-        } else if self.can_move(start, Dir::East) && self.can_move(start, Dir::West) {
-            Tile::Horizontal
-        } else {
-            Tile::SouthWest
+        // the start tile itself has no pipes yet, so only look at its neighbours
+        let dirs =
+            [Dir::North, Dir::East, Dir::South, Dir::West].map(|dir| self.connects(start, dir));
+        match dirs {
+            [true, true, _, _] => Tile::NorthEast,
+            [true, _, true, _] => Tile::Vertical,
+            [true, _, _, true] => Tile::NorthWest,
+            [_, true, true, _] => Tile::SouthEast,
+            [_, true, _, true] => Tile::Horizontal,
+            _ => Tile::SouthWest,
         }
     }
 
@@ -213,19 +221,124 @@ fn count_inside_tiles(map: &Map, pipe: HashSet<Position>) -> usize {
     inside_tiles
 }
 
-pub fn get_solution_1() -> usize {
-    let (position, map) = parse_input(INPUT);
+pub fn get_solution_1(input: &str) -> usize {
+    let (position, map) = parse_input(input);
 
     traverse(position, &map).len() / 2
 }
 
-pub fn get_solution_2() -> usize {
-    let (position, map) = parse_input(INPUT);
+pub fn get_solution_2(input: &str) -> usize {
+    let (position, map) = parse_input(input);
     let pipe = traverse(position, &map);
 
     count_inside_tiles(&map, pipe)
 }
 
+/// Checks that the map is a rectangle of pipes with a single start
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    checker.grid(&lines, "|-LJ7F.S");
+    checker.single(&lines, 'S');
+    checker.finish()
+}
+
+/// Generates a `size` x `size` map with a single loop and random pipes around it.
+///
+/// The loop runs along a straight bottom edge and a random staircase on top. The start is placed
+/// on any tile of the loop, bend or straight pipe.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let n = size.max(5);
+    let x0 = rng.range(0, n - 2);
+    let x1 = rng.range(x0 + 2, n);
+    let yb = rng.range(1, n);
+    let tops: Vec<usize> = (0..n).map(|_| rng.range(0, yb)).collect();
+
+    // the loop, as a list of positions in order
+    let mut pipe: Vec<Position> = (x0..=x1).map(|x| (x, yb)).collect();
+    pipe.extend((tops[x1]..yb).rev().map(|y| (x1, y)));
+    for x in (x0 + 1..x1).rev() {
+        let (from, to) = (tops[x + 1], tops[x]);
+        match from <= to {
+            true => pipe.extend((from..=to).map(|y| (x, y))),
+            false => pipe.extend((to..=from).rev().map(|y| (x, y))),
+        }
+    }
+    pipe.extend((tops[x0 + 1]..yb).map(|y| (x0, y)));
+
+    let mut grid = vec![vec!['.'; n]; n];
+    let len = pipe.len();
+    for (i, &(x, y)) in pipe.iter().enumerate() {
+        let dir = |(nx, ny): Position| match (nx.cmp(&x), ny.cmp(&y)) {
+            (Ordering::Greater, _) => 'E',
+            (Ordering::Less, _) => 'W',
+            (_, Ordering::Greater) => 'S',
+            _ => 'N',
+        };
+        let mut dirs = [dir(pipe[(i + len - 1) % len]), dir(pipe[(i + 1) % len])];
+        dirs.sort();
+        grid[y][x] = match dirs {
+            ['N', 'S'] => '|',
+            ['E', 'W'] => '-',
+            ['E', 'N'] => 'L',
+            ['N', 'W'] => 'J',
+            ['S', 'W'] => '7',
+            _ => 'F',
+        };
+    }
+
+    let &(sx, sy) = rng.pick(&pipe);
+    grid[sy][sx] = 'S';
+
+    // fill the rest with junk, but keep the start unambiguous
+    for (y, row) in grid.iter_mut().enumerate() {
+        for (x, tile) in row.iter_mut().enumerate() {
+            if *tile == '.' && sx.abs_diff(x) + sy.abs_diff(y) > 1 && rng.chance(40) {
+                *tile = *rng.pick(&['|', '-', 'L', 'J', '7', 'F']);
+            }
+        }
+    }
+
+    grid.into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
+}
+
+/// Tiles the map, only the first tile keeps the start, the other tiles have the pipe under the
+/// start instead
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    let grid: Vec<_> = inp.lines().map(str::as_bytes).collect();
+    let (x, y) = grid
+        .iter()
+        .enumerate()
+        .find_map(|(y, row)| row.iter().position(|&c| c == b'S').map(|x| (x, y)))
+        .ok_or("there is no start")?;
+    let connects = |dx: isize, dy: isize, pipes: &[u8]| {
+        grid.get(y.wrapping_add_signed(dy))
+            .and_then(|row| row.get(x.wrapping_add_signed(dx)))
+            .is_some_and(|c| pipes.contains(c))
+    };
+    let pipe = match (
+        connects(0, -1, b"|7F"),
+        connects(0, 1, b"|LJ"),
+        connects(-1, 0, b"-LF"),
+        connects(1, 0, b"-J7"),
+    ) {
+        (true, true, _, _) => '|',
+        (true, _, true, _) => 'J',
+        (true, _, _, true) => 'L',
+        (_, true, true, _) => '7',
+        (_, true, _, true) => 'F',
+        (_, _, true, true) => '-',
+        _ => return Err("the start doesn't connect to two pipes".into()),
+    };
+
+    Ok(scale::tile(inp, factor, |t, c| match c {
+        'S' if t != (0, 0) => pipe,
+        c => c,
+    }))
+}
+
 #[test]
 fn test_parse_input() {
     let (position, map) = parse_input(TEST);
@@ -258,3 +371,17 @@ fn test_count_inside_tiles() {
     let pipe = traverse(position, &map);
     println!("{}", count_inside_tiles(&map, pipe));
 }
+
+#[test]
+fn test_generate_start_on_any_tile() {
+    let mut starts = Vec::new();
+    for seed in 0..100 {
+        let inp = generate(&mut Rng::seed(seed), 10);
+        let (position, map) = parse_input(&inp);
+        assert!(traverse(position, &map).len() >= 4, "seed {seed}");
+        if !starts.contains(&map[position]) {
+            starts.push(map[position]);
+        }
+    }
+    assert_eq!(starts.len(), 6);
+}
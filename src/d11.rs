@@ -1,6 +1,25 @@
-#[allow(dead_code)]
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    checked::Checked,
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+};
+
 static TEST: &str = include_str!("../data/d11t");
-static INPUT: &str = include_str!("../data/d11");
+pub(crate) static INPUT: &str = include_str!("../data/d11");
+pub(crate) static TITLE: &str = "Cosmic Expansion";
+pub(crate) static EXAMPLES: &[Example] = &[
+    Example::new(TEST, &[1, 2]).params(&[("expansion", "10")]),
+    Example::new(TEST, &[2]).params(&[("expansion", "100")]),
+];
+
+pub(crate) static PARAMS: &[Param] = &[Param {
+    name: "expansion",
+    default: "1000000",
+    validate: params::positive,
+    help: "number of rows or columns an empty one expands to in part 2",
+}];
 
 type Position = [usize; 2];
 
@@ -27,7 +46,7 @@ impl Universe {
             for row in (0..len).filter(|p| galaxies.iter().all(|g| g[axis] != *p)) {
                 for (exp_galaxy, old_galaxy) in expanded.iter_mut().zip(&galaxies) {
                     if old_galaxy[axis] > row {
-                        exp_galaxy[axis] += rate;
+                        exp_galaxy[axis] = (Checked::new(exp_galaxy[axis], "expand") + rate).get();
                     }
                 }
             }
@@ -75,14 +94,34 @@ fn parse_input(input: &str) -> Universe {
     }
 }
 
-pub fn get_solution_1() -> usize {
-    parse_input(INPUT).expand(1).shortest_dists().iter().sum()
+pub fn get_solution_1(input: &str) -> usize {
+    parse_input(input).expand(1).shortest_dists().iter().sum()
 }
 
-pub fn get_solution_2() -> usize {
-    parse_input(INPUT)
-        .expand(999999)
+pub fn get_solution_2(input: &str, params: &Params) -> usize {
+    parse_input(input)
+        .expand(params.get::<usize>("expansion") - 1)
         .shortest_dists()
         .iter()
         .sum()
 }
+
+/// Checks that the image is a rectangle of `.` and `#`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    checker.grid(&lines, ".#");
+    checker.finish()
+}
+
+/// Generates a `size` x `size` image with about 4% galaxies
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        inp.extend((0..size).map(|_| if rng.chance(4) { '#' } else { '.' }));
+        inp.push('\n');
+    }
+
+    inp
+}
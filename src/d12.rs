@@ -1,8 +1,26 @@
-use std::collections::HashMap;
+use std::io::{self, BufRead};
+
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    bigint::BigUint,
+    checked::Checked,
+    diag::{Checker, Diagnostic},
+    hash::FastMap,
+    rng::Rng,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d12t");
-static INPUT: &str = include_str!("../data/d12");
+pub(crate) static INPUT: &str = include_str!("../data/d12");
+pub(crate) static TITLE: &str = "Hot Springs";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
+
+pub(crate) static PARAMS: &[Param] = &[Param {
+    name: "copies",
+    default: "5",
+    validate: params::positive,
+    help: "number of copies of each row after unfolding in part 2",
+}];
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Spring {
@@ -28,12 +46,12 @@ struct Entry {
 }
 
 impl Entry {
-    fn unfold(self) -> Self {
+    fn unfold(self, copies: usize) -> Self {
         let Self { springs, damaged } = self;
 
         Self {
-            springs: vec![springs.clone(); 5].join(&Spring::U),
-            damaged: vec![damaged.clone(); 5].concat(),
+            springs: vec![springs.clone(); copies].join(&Spring::U),
+            damaged: vec![damaged.clone(); copies].concat(),
         }
     }
 
@@ -41,8 +59,8 @@ impl Entry {
         &self,
         i: usize,
         j: usize,
-        seen: &mut HashMap<(usize, usize), usize>,
-    ) -> usize {
+        seen: &mut FastMap<(usize, usize), u64>,
+    ) -> u64 {
         // check if we already looked at this combination
         if let Some(matched) = seen.get(&(i, j)) {
             return *matched;
@@ -72,21 +90,23 @@ impl Entry {
             return 0;
         }
 
-        let mut matched = 0;
+        // a row fits into a u64, only the sum of the unfolded rows might not
+        let mut matched = Checked::new(0, "count_arrangements");
 
         // if its a valid arrangement, go to the next entry in the list of damaged springs
         if self.is_valid_arrangement(i, n) {
-            matched += self.count_arrangements(i + n + 1, j + 1, seen);
+            matched = matched + self.count_arrangements(i + n + 1, j + 1, seen);
         }
 
         // if the current spring would be damaged, this would be the last possible position
         // for the entry of the list, meaning we cannot look for any more arrangements with
         // this damaged entry.
         if self.springs[i] != Spring::D {
-            matched += self.count_arrangements(i + 1, j, seen);
+            matched = matched + self.count_arrangements(i + 1, j, seen);
         }
 
         // store the result of this call in the lookup table
+        let matched = matched.get();
         seen.insert((i, j), matched);
 
         matched
@@ -117,28 +137,112 @@ fn parse_input(input: &str) -> Vec<Entry> {
     input.lines().map(|line| line.into()).collect()
 }
 
-pub fn get_solution_1() -> usize {
-    parse_input(INPUT)
+/// One row per line
+pub(crate) fn show(inp: &str) -> String {
+    parse_input(inp)
+        .iter()
+        .map(|entry| format!("{entry:?}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn get_solution_1(input: &str) -> u64 {
+    parse_input(input)
         .into_iter()
-        .map(|s| s.count_arrangements(0, 0, &mut HashMap::new()))
+        .map(|s| s.count_arrangements(0, 0, &mut FastMap::default()))
         .sum()
 }
 
-pub fn get_solution_2() -> usize {
-    parse_input(INPUT)
+pub fn get_solution_2(input: &str, params: &Params) -> BigUint {
+    let copies = params.get("copies");
+    parse_input(input)
         .into_iter()
-        .map(|s| s.unfold().count_arrangements(0, 0, &mut HashMap::new()))
+        .map(|s| {
+            let count = s
+                .unfold(copies)
+                .count_arrangements(0, 0, &mut FastMap::default());
+            BigUint::from(count)
+        })
+        .sum()
+}
+
+pub(crate) fn stream_solution_1(reader: impl BufRead) -> io::Result<u64> {
+    reader
+        .lines()
+        .map(|line| {
+            line.map(|l| Entry::from(l.as_str()).count_arrangements(0, 0, &mut FastMap::default()))
+        })
+        .sum()
+}
+
+pub(crate) fn stream_solution_2(reader: impl BufRead, params: &Params) -> io::Result<BigUint> {
+    let copies = params.get("copies");
+    reader
+        .lines()
+        .map(|line| {
+            line.map(|l| {
+                let count = Entry::from(l.as_str()).unfold(copies).count_arrangements(
+                    0,
+                    0,
+                    &mut FastMap::default(),
+                );
+                BigUint::from(count)
+            })
+        })
         .sum()
 }
 
+/// Checks that every line has the form `<springs> <group>,<group>,...`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        let Some((springs, groups)) = checker.split_once(line, " ") else {
+            continue;
+        };
+        checker.chars(springs, ".#?");
+        for group in groups.split(',') {
+            checker.parse::<usize>(group, "a group size");
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` rows of springs. Each row is a valid arrangement, where a random share of
+/// springs is replaced with '?', up to all of them.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        let damaged: Vec<usize> = (0..rng.range(1, 6)).map(|_| rng.range(1, 6)).collect();
+        let mut springs = ".".repeat(rng.range(0, 3));
+        for (i, n) in damaged.iter().enumerate() {
+            if i > 0 {
+                springs.push_str(&".".repeat(rng.range(1, 4)));
+            }
+            springs.push_str(&"#".repeat(*n));
+        }
+        springs.push_str(&".".repeat(rng.range(0, 3)));
+
+        let unknown = rng.range(30, 101);
+        let springs: String = springs
+            .chars()
+            .map(|c| if rng.chance(unknown) { '?' } else { c })
+            .collect();
+        let damaged: Vec<_> = damaged.iter().map(|n| n.to_string()).collect();
+        inp.push_str(&format!("{springs} {}\n", damaged.join(",")));
+    }
+
+    inp
+}
+
 #[test]
 fn test_find_arrangements_rec1() {
     let spring: Entry = "???.### 1,1,3".into();
     assert_eq!(
         1,
         spring
-            .unfold()
-            .count_arrangements(0, 0, &mut HashMap::new())
+            .unfold(5)
+            .count_arrangements(0, 0, &mut FastMap::default())
     );
 }
 
@@ -148,8 +252,8 @@ fn test_find_arrangements_rec2() {
     assert_eq!(
         16384,
         spring
-            .unfold()
-            .count_arrangements(0, 0, &mut HashMap::new())
+            .unfold(5)
+            .count_arrangements(0, 0, &mut FastMap::default())
     );
 }
 
@@ -159,8 +263,8 @@ fn test_find_arrangements_rec3() {
     assert_eq!(
         1,
         spring
-            .unfold()
-            .count_arrangements(0, 0, &mut HashMap::new())
+            .unfold(5)
+            .count_arrangements(0, 0, &mut FastMap::default())
     );
 }
 
@@ -170,8 +274,8 @@ fn test_find_arrangements_rec4() {
     assert_eq!(
         16,
         spring
-            .unfold()
-            .count_arrangements(0, 0, &mut HashMap::new())
+            .unfold(5)
+            .count_arrangements(0, 0, &mut FastMap::default())
     );
 }
 
@@ -181,8 +285,8 @@ fn test_find_arrangements_rec5() {
     assert_eq!(
         2500,
         spring
-            .unfold()
-            .count_arrangements(0, 0, &mut HashMap::new())
+            .unfold(5)
+            .count_arrangements(0, 0, &mut FastMap::default())
     );
 }
 
@@ -192,7 +296,88 @@ fn test_find_arrangements_rec6() {
     assert_eq!(
         506250,
         spring
-            .unfold()
-            .count_arrangements(0, 0, &mut HashMap::new())
+            .unfold(5)
+            .count_arrangements(0, 0, &mut FastMap::default())
+    );
+}
+
+#[test]
+fn test_arrangements_overflow() {
+    use crate::util::checked;
+
+    // 506250 arrangements for 5 copies, every copy multiplies them by about 15
+    let spring: Entry = "?###???????? 3,2,1".into();
+    let count = || {
+        spring
+            .clone()
+            .unfold(20)
+            .count_arrangements(0, 0, &mut FastMap::default())
+    };
+    assert!(checked::catch_checked(count).is_err());
+}
+
+#[cfg(test)]
+fn count_arrangements_brute_force(entry: &Entry) -> usize {
+    let unknown: Vec<usize> = (0..entry.springs.len())
+        .filter(|i| entry.springs[*i] == Spring::U)
+        .collect();
+
+    (0..1_usize << unknown.len())
+        .filter(|mask| {
+            let mut springs = entry.springs.clone();
+            for (bit, i) in unknown.iter().enumerate() {
+                springs[*i] = if mask & (1 << bit) == 0 {
+                    Spring::O
+                } else {
+                    Spring::D
+                };
+            }
+            let groups: Vec<usize> = springs
+                .split(|s| *s == Spring::O)
+                .map(|group| group.len())
+                .filter(|len| *len > 0)
+                .collect();
+            groups == entry.damaged
+        })
+        .count()
+}
+
+#[test]
+fn test_count_arrangements_matches_brute_force() {
+    use crate::util::prop::{self, shrink_each, shrink_usize, shrink_vec};
+
+    prop::check(
+        "count_arrangements",
+        |rng, size| Entry {
+            springs: (0..rng.range(1, size.min(14) + 2))
+                .map(|_| *rng.pick(&[Spring::O, Spring::D, Spring::U, Spring::U]))
+                .collect(),
+            damaged: (0..rng.range(1, 4)).map(|_| rng.range(1, 4)).collect(),
+        },
+        |entry| {
+            let mut smaller: Vec<Entry> = shrink_vec(&entry.springs)
+                .into_iter()
+                .map(|springs| Entry {
+                    springs,
+                    damaged: entry.damaged.clone(),
+                })
+                .collect();
+            smaller.extend(
+                shrink_vec(&entry.damaged)
+                    .into_iter()
+                    .chain(shrink_each(&entry.damaged, |n| shrink_usize(*n, 1)))
+                    .map(|damaged| Entry {
+                        springs: entry.springs.clone(),
+                        damaged,
+                    }),
+            );
+            smaller
+        },
+        |entry| {
+            prop::assert_eq(
+                entry.count_arrangements(0, 0, &mut FastMap::default()),
+                count_arrangements_brute_force(entry) as u64,
+            )
+        },
     );
 }
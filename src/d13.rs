@@ -1,8 +1,15 @@
-use crate::util::Rotate;
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    explain::Explanation,
+    rng::Rng,
+    Rotate,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d13t");
-static INPUT: &str = include_str!("../data/d13");
+pub(crate) static INPUT: &str = include_str!("../data/d13");
+pub(crate) static TITLE: &str = "Point of Incidence";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
 type Pattern = Vec<Vec<char>>;
 
@@ -63,22 +70,120 @@ fn find_reflection(pattern: &Pattern, diff: usize) -> Reflection {
     panic!("found no reflection");
 }
 
+impl Reflection {
+    fn value(&self) -> usize {
+        match self {
+            Reflection::Row(i) => i * 100,
+            Reflection::Col(i) => *i,
+        }
+    }
+}
+
 fn summarize(patterns: Vec<Pattern>, diff: usize) -> usize {
     patterns
         .into_iter()
-        .map(|pattern| match find_reflection(&pattern, diff) {
-            Reflection::Row(i) => i * 100,
-            Reflection::Col(i) => i,
-        })
+        .map(|pattern| find_reflection(&pattern, diff).value())
         .sum()
 }
 
-pub fn get_solution_1() -> usize {
-    summarize(parse_input(INPUT), 0)
+pub fn get_solution_1(input: &str) -> usize {
+    summarize(parse_input(input), 0)
 }
 
-pub fn get_solution_2() -> usize {
-    summarize(parse_input(INPUT), 1)
+pub fn get_solution_2(input: &str) -> usize {
+    summarize(parse_input(input), 1)
+}
+
+/// A row per pattern with its line of reflection, which has to differ by one smudge in part 2
+pub(crate) fn explain(inp: &str, part: usize) -> Explanation {
+    let mut explanation = Explanation::new(
+        match part {
+            1 => {
+                "the sum of the columns left of the vertical lines of reflection and 100 times the \
+                  rows above the horizontal ones"
+            }
+            _ => {
+                "the sum of the columns left of the vertical lines of reflection and 100 times the \
+                  rows above the horizontal ones, once the smudge is cleaned"
+            }
+        },
+        &["pattern", "size", "reflection", "value"],
+    );
+    for (i, pattern) in parse_input(inp).iter().enumerate() {
+        let reflection = find_reflection(pattern, part - 1);
+        let line = match reflection {
+            Reflection::Row(r) => format!("between rows {r} and {}", r + 1),
+            Reflection::Col(c) => format!("between columns {c} and {}", c + 1),
+        };
+        let size = format!("{}x{}", pattern[0].len(), pattern.len());
+        explanation.push(vec![
+            (i + 1).into(),
+            size.into(),
+            line.into(),
+            reflection.value().into(),
+        ]);
+    }
+    explanation
+}
+
+/// Checks that the patterns are rectangles of `.` and `#`, separated by empty lines
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for pattern in checker.blocks() {
+        checker.grid(&pattern, ".#");
+    }
+    checker.finish()
+}
+
+/// Generates `size` patterns. Each pattern has a perfect horizontal reflection and a vertical
+/// reflection with exactly one smudge.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut patterns = Vec::new();
+
+    for _ in 0..size {
+        let (height, width) = (rng.range(3, 16), rng.range(2, 16));
+        // the row reflection may not be in the center, some rows need to be left unreflected
+        let row = loop {
+            let row = rng.range(1, height);
+            if row * 2 != height {
+                break row;
+            }
+        };
+        let col = rng.range(1, width);
+
+        let mut pattern: Pattern = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| *rng.pick(&['#', '.']))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        for line in &mut pattern {
+            for c in (0..col).filter(|c| 2 * col - 1 - c < width) {
+                line[2 * col - 1 - c] = line[c];
+            }
+        }
+        for r in (0..row).filter(|r| 2 * row - 1 - r < height) {
+            pattern[2 * row - 1 - r] = pattern[r].clone();
+        }
+
+        // put the smudge into a row which isn't reflected
+        let r = match row * 2 < height {
+            true => rng.range(row * 2, height),
+            false => rng.range(0, row * 2 - height),
+        };
+        let c = rng.range((2 * col).saturating_sub(width), col);
+        pattern[r][c] = if pattern[r][c] == '#' { '.' } else { '#' };
+
+        patterns.push(
+            pattern
+                .into_iter()
+                .map(|line| line.into_iter().chain(['\n']).collect::<String>())
+                .collect::<String>(),
+        );
+    }
+
+    patterns.join("\n")
 }
 
 #[test]
@@ -98,3 +203,13 @@ fn test_summarize() {
 fn test_flipped() {
     assert_eq!(400, summarize(parse_input(TEST), 1));
 }
+
+#[test]
+fn test_generate() {
+    let patterns = parse_input(&generate(&mut Rng::seed(13), 50));
+    assert_eq!(patterns.len(), 50);
+    for pattern in &patterns {
+        assert!(matches!(find_reflection(pattern, 0), Reflection::Row(_)));
+        find_reflection(pattern, 1);
+    }
+}
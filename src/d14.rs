@@ -1,122 +1,228 @@
-use std::collections::HashMap;
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    bits::BitGrid,
+    diag::{Checker, Diagnostic},
+    frames,
+    hash::FastMap,
+    progress::{self, Progress},
+    rng::Rng,
+    scale,
+};
 
-use crate::util::Rotate;
-
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d14t");
-static INPUT: &str = include_str!("../data/d14");
-
-const N_CYCLES: usize = 1000000000;
-
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|l| l.chars().collect()).collect()
+pub(crate) static INPUT: &str = include_str!("../data/d14");
+pub(crate) static TITLE: &str = "Parabolic Reflector Dish";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
+
+pub(crate) static PARAMS: &[Param] = &[Param {
+    name: "cycles",
+    default: "1000000000",
+    validate: params::count,
+    help: "number of spin cycles in part 2",
+}];
+
+/// The rounded rocks, which roll, and the cube-shaped rocks, which don't
+struct Platform {
+    rounded: BitGrid,
+    cubes: BitGrid,
 }
 
-fn tilt_up(mut grid: Vec<Vec<char>>) -> Vec<Vec<char>> {
-    let mut new_grid = vec![grid.remove(0)];
-
-    for line in grid.into_iter() {
-        // push an empty grid with no rocks
-        new_grid.push(
-            line.iter()
-                .map(|c| if *c == 'O' { '.' } else { *c })
-                .collect(),
-        );
-        for (j, c) in line.iter().copied().enumerate() {
-            if c != 'O' {
-                continue;
+fn parse_input(input: &str) -> Platform {
+    let width = input.lines().next().map_or(0, |l| l.len());
+    let height = input.lines().count();
+    let mut rounded = BitGrid::new(width, height);
+    let mut cubes = BitGrid::new(width, height);
+    for (y, l) in input.lines().enumerate() {
+        for (x, c) in l.chars().enumerate() {
+            match c {
+                'O' => rounded.insert(x, y),
+                '#' => cubes.insert(x, y),
+                _ => (),
             }
-            for k in (0..new_grid.len() - 1).rev() {
-                if new_grid[k][j] != '.' {
-                    new_grid[k + 1][j] = 'O';
-                    break;
-                } else if k == 0 {
-                    new_grid[k][j] = 'O';
-                }
+        }
+    }
+    Platform { rounded, cubes }
+}
+
+/// A move of every cell of a grid by one
+type Shift = fn(&BitGrid) -> BitGrid;
+
+impl Platform {
+    // moves all rounded rocks which are free to move by one with `forward` until none of them
+    // can move anymore, `back` is the opposite move
+    fn tilt(&mut self, forward: Shift, back: Shift) {
+        loop {
+            let blocked = &self.rounded | &self.cubes;
+            let moved = forward(&self.rounded).without(&blocked);
+            if moved.is_empty() {
+                break;
             }
+            self.rounded = &self.rounded.without(&back(&moved)) | &moved;
         }
     }
 
-    new_grid
-}
+    fn tilt_up(&mut self) {
+        self.tilt(BitGrid::up, BitGrid::down);
+    }
 
-fn do_cycle(mut grid: Vec<Vec<char>>) -> Vec<Vec<char>> {
-    // always tilt up and just rotate the grid
-    for _ in 0..4 {
-        grid = tilt_up(grid);
-        grid = grid.rotate();
+    fn do_cycle(&mut self) {
+        self.tilt_up();
+        self.tilt(BitGrid::left, BitGrid::right);
+        self.tilt(BitGrid::down, BitGrid::up);
+        self.tilt(BitGrid::right, BitGrid::left);
     }
 
-    grid
-}
+    fn render(&self) -> String {
+        let mut buf = String::new();
+        for y in 0..self.height() {
+            buf.extend((0..self.width()).map(|x| {
+                match (self.rounded.contains(x, y), self.cubes.contains(x, y)) {
+                    (true, _) => 'O',
+                    (_, true) => '#',
+                    _ => '.',
+                }
+            }));
+            buf.push('\n');
+        }
+        buf
+    }
+
+    fn width(&self) -> usize {
+        self.rounded.width()
+    }
+
+    fn height(&self) -> usize {
+        self.rounded.height()
+    }
 
-fn get_total_load(grid: &[Vec<char>]) -> usize {
-    grid.iter()
-        .rev()
-        .enumerate()
-        .map(|(i, l)| l.iter().filter(|c| **c == 'O').count() * (i + 1))
-        .sum()
+    fn get_total_load(&self) -> usize {
+        (0..self.height())
+            .map(|y| self.rounded.count_row(y) * (self.height() - y))
+            .sum()
+    }
 }
 
-fn find_repetition(mut grid: Vec<Vec<char>>) -> usize {
-    let mut seen = HashMap::new();
+fn find_repetition(mut platform: Platform, n_cycles: usize) -> usize {
+    // the cube-shaped rocks never move, so the rounded ones identify the state
+    let mut seen = FastMap::default();
 
-    seen.insert(grid.clone(), 0);
+    seen.insert(platform.rounded.clone(), 0);
 
-    for i in 1.. {
-        grid = do_cycle(grid);
+    for i in 1..=n_cycles {
+        platform.do_cycle();
+        frames::emit("d14", || platform.render());
+        progress::report(|| Progress {
+            iterations: i,
+            states: Some(seen.len()),
+            ..Default::default()
+        });
         // try to find a cycle in the patterns
-        if let Some(start) = seen.get(&grid) {
+        if let Some(start) = seen.get(&platform.rounded) {
             // cycle was found, see how many more grid cycles are needed to end up
-            // at the same value we would have at N_CYCLES
-            let j = (N_CYCLES - i) % (i - start);
+            // at the same value we would have after n_cycles
+            let j = (n_cycles - i) % (i - start);
             for _ in 0..j {
-                grid = do_cycle(grid);
+                platform.do_cycle();
             }
-            return get_total_load(&grid);
+            return platform.get_total_load();
         }
-        seen.insert(grid.clone(), i);
+        seen.insert(platform.rounded.clone(), i);
     }
 
-    unreachable!();
+    platform.get_total_load()
 }
 
-pub fn get_solution_1() -> usize {
-    get_total_load(&tilt_up(parse_input(INPUT)))
+pub fn get_solution_1(input: &str) -> usize {
+    let mut platform = parse_input(input);
+    platform.tilt_up();
+    platform.get_total_load()
 }
 
-pub fn get_solution_2() -> usize {
-    find_repetition(parse_input(INPUT))
+pub fn get_solution_2(input: &str, params: &Params) -> usize {
+    find_repetition(parse_input(input), params.get("cycles"))
+}
+
+/// Checks that the platform is a rectangle of rocks and empty spaces
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    checker.grid(&lines, ".#O");
+    checker.finish()
+}
+
+/// Generates a `size` x `size` platform
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        inp.extend((0..size).map(|_| match rng.range(0, 100) {
+            0..=19 => 'O',
+            20..=34 => '#',
+            _ => '.',
+        }));
+        inp.push('\n');
+    }
+
+    inp
+}
+
+/// Tiles the platform `factor` times in both directions
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    Ok(scale::tile(inp, factor, |_, c| c))
 }
 
 #[test]
 fn test_tilt_up() {
-    let grid = parse_input(TEST);
-    let new_grid = tilt_up(grid);
-    for line in new_grid {
-        println!("{:?}", line)
-    }
+    let mut platform = parse_input(TEST);
+    platform.tilt_up();
+    assert_eq!(
+        platform.render(),
+        "\
+OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#....
+"
+    );
 }
 
 #[test]
 fn test_get_total_load() {
-    let grid = parse_input(TEST);
-    let new_grid = tilt_up(grid);
-    assert_eq!(136, get_total_load(&new_grid));
+    let mut platform = parse_input(TEST);
+    platform.tilt_up();
+    assert_eq!(136, platform.get_total_load());
 }
 
 #[test]
 fn test_do_cycle() {
-    let grid = parse_input(TEST);
-    let new_grid = do_cycle(grid);
-
-    for line in new_grid {
-        println!("{}", line.into_iter().collect::<String>());
-    }
+    let mut platform = parse_input(TEST);
+    platform.do_cycle();
+    assert_eq!(
+        platform.render(),
+        "\
+.....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+"
+    );
 }
 
 #[test]
 fn test_find_repetition() {
-    let grid = parse_input(TEST);
-    assert_eq!(64, find_repetition(grid));
+    let platform = parse_input(TEST);
+    assert_eq!(64, find_repetition(platform, 1000000000));
 }
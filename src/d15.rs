@@ -1,8 +1,18 @@
-use std::num::ParseIntError;
+use std::{
+    io::{self, BufRead},
+    num::ParseIntError,
+};
+
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+};
 
-#[allow(dead_code)]
 static TEST: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-static INPUT: &str = include_str!("../data/d15");
+pub(crate) static INPUT: &str = include_str!("../data/d15");
+pub(crate) static TITLE: &str = "Lens Library";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
 const CAP: usize = 256;
 const EMPTY: Vec<&str> = Vec::new();
@@ -11,10 +21,7 @@ struct AocHashMap<T: Lens, const C: usize = CAP> {
     buckets: [Vec<T>; C],
 }
 
-impl<T: Lens, const C: usize> AocHashMap<T, C>
-where
-    for<'a> <T as Lens>::Item<'a>: PartialEq,
-{
+impl<T: Lens, const C: usize> AocHashMap<T, C> {
     fn remove(&mut self, lens: T) -> Option<T> {
         let bucket_index = lens.hash();
         match self.buckets[bucket_index]
@@ -57,7 +64,8 @@ where
 }
 
 trait Lens {
-    type Item<'a>
+    // bounded here rather than with `for<'a>` on the map, which would require `T: 'static`
+    type Item<'a>: PartialEq
     where
         Self: 'a;
 
@@ -69,16 +77,19 @@ trait Lens {
     fn focusing_power(&self) -> Result<usize, ParseIntError>;
 }
 
-impl Lens for &str {
+// for the steps in the input and the owned steps read from a stream
+impl<S: AsRef<str>> Lens for S {
     type Item<'a> = &'a str where Self: 'a;
 
     fn label(&self) -> Self::Item<'_> {
-        &self[..self.find(['-', '=']).unwrap()]
+        let step = self.as_ref();
+        &step[..step.find(['-', '=']).unwrap()]
     }
 
     fn op(&self) -> Self::Item<'_> {
-        let idx = self.find(['-', '=']).unwrap();
-        &(*self)[idx..idx + 1]
+        let step = self.as_ref();
+        let idx = step.find(['-', '=']).unwrap();
+        &step[idx..idx + 1]
     }
 
     fn comp(&self, other: &Self) -> bool {
@@ -90,13 +101,15 @@ impl Lens for &str {
     }
 
     fn hash_p1(&self) -> usize {
-        self.bytes()
+        self.as_ref()
+            .bytes()
             .fold(0, |acc, b| ((acc + b as usize) * 17) % 256)
     }
 
     fn focusing_power(&self) -> Result<usize, ParseIntError> {
-        let idx = self.find(['-', '=']).unwrap();
-        str::parse::<usize>(&self[idx + 1..])
+        let step = self.as_ref();
+        let idx = step.find(['-', '=']).unwrap();
+        str::parse::<usize>(&step[idx + 1..])
     }
 }
 
@@ -104,18 +117,18 @@ fn parse_input(input: &str) -> Vec<&str> {
     input.trim_end().split(',').collect()
 }
 
-pub fn get_solution_1() -> usize {
-    parse_input(INPUT)
+pub fn get_solution_1(input: &str) -> usize {
+    parse_input(input)
         .into_iter()
         .map(|s| s.hash())
         .sum::<usize>()
 }
 
-pub fn get_solution_2() -> usize {
+pub fn get_solution_2(input: &str) -> usize {
     let mut map = AocHashMap::<&str, CAP> {
         buckets: [EMPTY; CAP],
     };
-    for lens in parse_input(INPUT) {
+    for lens in parse_input(input) {
         match lens.op() {
             "=" => map.insert(lens),
             "-" => map.remove(lens),
@@ -125,6 +138,89 @@ pub fn get_solution_2() -> usize {
     map.focusing_power()
 }
 
+// the steps of the input, read up to the next comma
+fn read_steps(reader: impl BufRead) -> impl Iterator<Item = io::Result<String>> {
+    reader.split(b',').map(|step| {
+        let step =
+            String::from_utf8(step?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(step.trim_end().to_string())
+    })
+}
+
+pub(crate) fn stream_solution_1(reader: impl BufRead) -> io::Result<usize> {
+    read_steps(reader).map(|step| step.map(|s| s.hash())).sum()
+}
+
+pub(crate) fn stream_solution_2(reader: impl BufRead) -> io::Result<usize> {
+    let mut map = AocHashMap::<String, CAP> {
+        buckets: [const { Vec::new() }; CAP],
+    };
+    for lens in read_steps(reader) {
+        let lens = lens?;
+        match lens.op() {
+            "=" => map.insert(lens),
+            "-" => map.remove(lens),
+            _ => panic!("found invalid operation"),
+        };
+    }
+    Ok(map.focusing_power())
+}
+
+/// Checks that the input is a single line of steps of the form `<label>=<focal length>` or
+/// `<label>-`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    for line in lines.iter().skip(1) {
+        checker.error(line, "expected all steps on a single line");
+    }
+
+    for step in lines.first().into_iter().flat_map(|l| l.split(',')) {
+        let label = match step.strip_suffix('-') {
+            Some(label) => label,
+            None => {
+                let Some((label, focal)) = checker.split_once(step, "=") else {
+                    continue;
+                };
+                if checker
+                    .parse::<usize>(focal, "a focal length")
+                    .is_some_and(|f| !(1..=9).contains(&f))
+                {
+                    checker.error(focal, "expected a focal length from 1 to 9");
+                }
+                label
+            }
+        };
+        if label.is_empty() {
+            checker.error(step, "expected a label");
+        }
+        checker.chars(label, "abcdefghijklmnopqrstuvwxyz");
+    }
+    checker.finish()
+}
+
+/// Generates `size` steps, labels are drawn from a pool so lenses get replaced and removed
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let labels: Vec<String> = (0..size / 4 + 1)
+        .map(|_| {
+            let len = rng.range(1, 7);
+            rng.word(len)
+        })
+        .collect();
+
+    let steps: Vec<String> = (0..size)
+        .map(|_| {
+            let label = rng.pick(&labels);
+            match rng.chance(30) {
+                true => format!("{label}-"),
+                false => format!("{label}={}", rng.range(1, 10)),
+            }
+        })
+        .collect();
+
+    format!("{}\n", steps.join(","))
+}
+
 #[test]
 fn test_hash() {
     assert_eq!(
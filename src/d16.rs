@@ -1,10 +1,20 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
+
+use crate::days::Example;
+use crate::util::{
+    bits::BitSet,
+    diag::{Checker, Diagnostic},
+    frames,
+    rng::Rng,
+    scale,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d16t");
-static INPUT: &str = include_str!("../data/d16");
+pub(crate) static INPUT: &str = include_str!("../data/d16");
+pub(crate) static TITLE: &str = "The Floor Will Be Lava";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Dir {
     Up = 0,
     Right = 1,
@@ -79,8 +89,27 @@ fn parse_input(input: &str) -> Vec<Vec<char>> {
         .collect()
 }
 
-fn bfs(map: &[Vec<char>], start: (usize, usize), dir: Dir) -> HashSet<(usize, usize)> {
-    let mut seen: HashSet<((usize, usize), Dir)> = HashSet::new();
+fn render(map: &[Vec<char>], energized: &BitSet) -> String {
+    let width = map.first().map_or(0, |row| row.len());
+    let mut buf = String::new();
+    for (y, row) in map.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            match *tile == '.' && energized.contains(x + y * width) {
+                true => buf.push('#'),
+                false => buf.push(*tile),
+            }
+        }
+        buf.push('\n');
+    }
+    buf
+}
+
+/// Returns the energized tiles, as `x + y * width`
+fn bfs(map: &[Vec<char>], start: (usize, usize), dir: Dir) -> BitSet {
+    let width = map.first().map_or(0, |row| row.len());
+    // the beams seen so far, as `(x + y * width) * 4 + dir`
+    let mut seen = BitSet::new(width * map.len() * 4);
+    let mut energized = BitSet::new(width * map.len());
     let mut queue = VecDeque::from([(start, dir)]);
 
     while let Some((pos, dir)) = queue.pop_front() {
@@ -92,9 +121,12 @@ fn bfs(map: &[Vec<char>], start: (usize, usize), dir: Dir) -> HashSet<(usize, us
             _ => continue,
         };
         // check if we've been here before
-        if !seen.insert((pos, dir)) {
+        let tile_index = pos.0 + pos.1 * width;
+        if !seen.insert(tile_index * 4 + dir as usize) {
             continue; // we've been here before
         }
+        energized.insert(tile_index);
+        frames::emit("d16", || render(map, &energized));
         // move the position
         let (first_dir, second_dir_opt) = dir.next_dir(tile);
         let first_pos = pos.move_pos(&first_dir);
@@ -107,10 +139,10 @@ fn bfs(map: &[Vec<char>], start: (usize, usize), dir: Dir) -> HashSet<(usize, us
         }
     }
 
-    seen.into_iter().map(|(pos, _)| pos).collect()
+    energized
 }
 
-fn bfs_all_positions(map: &[Vec<char>]) -> HashSet<(usize, usize)> {
+fn bfs_all_positions(map: &[Vec<char>]) -> usize {
     let width = map.first().map(|row| row.len()).unwrap_or(0);
     let height = map.len();
 
@@ -119,17 +151,45 @@ fn bfs_all_positions(map: &[Vec<char>]) -> HashSet<(usize, usize)> {
         .chain((0..width).map(|x| ((x, height - 1), Dir::Up)))
         .chain((0..height).map(|y| ((0, y), Dir::Right)))
         .chain((0..height).map(|y| ((width - 1, y), Dir::Left)))
-        .map(|(pos, dir)| bfs(map, pos, dir))
-        .max_by(|a, b| a.len().cmp(&b.len()))
-        .unwrap_or(HashSet::new())
+        .map(|(pos, dir)| bfs(map, pos, dir).count())
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn get_solution_1(input: &str) -> usize {
+    let map = parse_input(input);
+    bfs(&map, (0, 0), Dir::Right).count()
 }
 
-pub fn get_solution_1() -> usize {
-    let map = parse_input(INPUT);
-    bfs(&map, (0, 0), Dir::Right).len()
+pub fn get_solution_2(input: &str) -> usize {
+    let map = parse_input(input);
+    bfs_all_positions(&map)
+}
+
+/// Checks that the contraption is a rectangle of empty spaces, mirrors and splitters
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    checker.grid(&lines, ".|-/\\");
+    checker.finish()
+}
+
+/// Generates a `size` x `size` contraption with about 15% mirrors and splitters
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        inp.extend((0..size).map(|_| match rng.chance(15) {
+            true => *rng.pick(&['/', '\\', '|', '-']),
+            false => '.',
+        }));
+        inp.push('\n');
+    }
+
+    inp
 }
 
-pub fn get_solution_2() -> usize {
-    let map = parse_input(INPUT);
-    bfs_all_positions(&map).len()
+/// Tiles the contraption `factor` times in both directions
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    Ok(scale::tile(inp, factor, |_, c| c))
 }
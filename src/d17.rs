@@ -1,14 +1,50 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{BinaryHeap, HashSet},
+    collections::BinaryHeap,
     hash::Hash,
 };
 
-#[allow(dead_code)]
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    hash::FastSet,
+    rng::Rng,
+    scale,
+};
+
 static TEST: &str = include_str!("../data/d17t");
-#[allow(dead_code)]
 static TEST2: &str = include_str!("../data/d17t2");
-static INPUT: &str = include_str!("../data/d17");
+pub(crate) static INPUT: &str = include_str!("../data/d17");
+pub(crate) static TITLE: &str = "Clumsy Crucible";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2]), Example::new(TEST2, &[2])];
+
+pub(crate) static PARAMS: &[Param] = &[
+    Param {
+        name: "min_steps",
+        default: "1",
+        validate: params::count,
+        help: "steps the crucible has to move before turning",
+    },
+    Param {
+        name: "max_steps",
+        default: "3",
+        validate: params::positive,
+        help: "steps the crucible can move in a straight line",
+    },
+    Param {
+        name: "ultra_min_steps",
+        default: "4",
+        validate: params::count,
+        help: "steps the ultra crucible has to move before turning or stopping",
+    },
+    Param {
+        name: "ultra_max_steps",
+        default: "10",
+        validate: params::positive,
+        help: "steps the ultra crucible can move in a straight line",
+    },
+];
 
 fn parse_input(input: &str) -> Vec<Vec<u8>> {
     input
@@ -73,19 +109,12 @@ impl Node {
         }
     }
 
-    fn can_move_p1(&self, dir: Dir) -> Option<usize> {
-        match (self.dir == dir, self.steps == 3) {
-            (true, true) => None,
-            (true, false) => Some(self.steps + 1),
-            _ => Some(1),
-        }
-    }
-
-    fn can_move_p2(&self, dir: Dir) -> Option<usize> {
+    // the crucible has to move at least min and at most max steps in a straight line
+    fn can_move(&self, dir: Dir, min: usize, max: usize) -> Option<usize> {
         match (self.dir == dir, self.steps) {
-            (true, 10) => None,
+            (true, steps) if steps >= max => None,
             (true, steps) => Some(steps + 1),
-            (false, steps) if steps < 4 => None,
+            (false, steps) if steps < min => None,
             _ => Some(1),
         }
     }
@@ -127,7 +156,7 @@ where
     let right = Node::new(0, (0, 0), Dir::Right, 0);
     let goal = (map[0].len() - 1, map.len() - 1);
 
-    let mut seen = HashSet::new();
+    let mut seen = FastSet::default();
     let mut queue = BinaryHeap::from([Reverse(down), Reverse(right)]);
 
     while let Some(Reverse(node)) = queue.pop() {
@@ -164,10 +193,39 @@ where
     panic!("couldn't reach the goal");
 }
 
-pub fn get_solution_1() -> usize {
-    dijkstra(&parse_input(INPUT), Node::can_move_p1, 0)
+pub fn get_solution_1(input: &str, params: &Params) -> usize {
+    let (min, max) = (params.get("min_steps"), params.get("max_steps"));
+    dijkstra(&parse_input(input), |n, d| n.can_move(d, min, max), min)
+}
+
+pub fn get_solution_2(input: &str, params: &Params) -> usize {
+    let (min, max) = (params.get("ultra_min_steps"), params.get("ultra_max_steps"));
+    dijkstra(&parse_input(input), |n, d| n.can_move(d, min, max), min)
+}
+
+/// Checks that the map is a rectangle of heat losses from 1 to 9
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    checker.grid(&lines, "123456789");
+    checker.finish()
+}
+
+/// Generates a `size` x `size` map, with at least 5 x 5 blocks so the ultra crucible can reach the
+/// goal
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5);
+    let mut inp = String::new();
+
+    for _ in 0..size {
+        inp.extend((0..size).map(|_| (b'0' + rng.range(1, 10) as u8) as char));
+        inp.push('\n');
+    }
+
+    inp
 }
 
-pub fn get_solution_2() -> usize {
-    dijkstra(&parse_input(INPUT), Node::can_move_p2, 4)
+/// Tiles the city `factor` times in both directions
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    Ok(scale::tile(inp, factor, |_, c| c))
 }
@@ -1,6 +1,16 @@
-#[allow(dead_code)]
+use std::io::{self, BufRead};
+
+use crate::days::Example;
+use crate::util::{
+    checked::Checked,
+    diag::{Checker, Diagnostic},
+    rng::Rng,
+};
+
 static TEST: &str = include_str!("../data/d18t");
-static INPUT: &str = include_str!("../data/d18");
+pub(crate) static INPUT: &str = include_str!("../data/d18");
+pub(crate) static TITLE: &str = "Lavaduct Lagoon";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 enum Dir {
@@ -33,15 +43,15 @@ fn parse_hex(hex: &str) -> (isize, Dir) {
     )
 }
 
-struct DigEntry {
+struct DigEntry<'a> {
     dir: Dir,
     steps: usize,
-    color: &'static str,
+    color: &'a str,
 }
 
-impl TryFrom<&'static str> for DigEntry {
+impl<'a> TryFrom<&'a str> for DigEntry<'a> {
     type Error = &'static str;
-    fn try_from(inp: &'static str) -> Result<Self, Self::Error> {
+    fn try_from(inp: &'a str) -> Result<Self, Self::Error> {
         let mut iter = inp.split_whitespace();
         let dir = iter.next().ok_or("empty line")?.try_into()?;
         let steps = iter
@@ -58,42 +68,53 @@ impl TryFrom<&'static str> for DigEntry {
     }
 }
 
-fn parse_input(inp: &'static str) -> Result<Vec<DigEntry>, &'static str> {
+fn parse_input(inp: &str) -> Result<Vec<DigEntry<'_>>, &'static str> {
     inp.lines().map(|l| l.try_into()).collect()
 }
 
-fn picks_theorem(map: &[(isize, Dir)]) -> isize {
-    let mut area: isize = 0;
-    let mut x = 0;
-    let mut y = 0;
-    let mut y_1 = y;
-    let mut x_1 = x;
-    let mut perimeter = 0;
+/// The lagoon dug so far, its area is the sum of the trapezoids under the edges (shoelace
+/// formula) and with the trench itself by Pick's theorem
+#[derive(Default)]
+struct Lagoon {
+    x: isize,
+    y: isize,
+    area: isize,
+    perimeter: isize,
+}
 
-    for (d, dir) in map {
+impl Lagoon {
+    fn dig(&mut self, d: isize, dir: Dir) {
+        let (mut x_1, mut y_1) = (self.x, self.y);
         match dir {
             Dir::Up => y_1 -= d,
             Dir::Down => y_1 += d,
-            Dir::Right => {
-                x_1 += d;
-            }
-            Dir::Left => {
-                x_1 -= d;
-            }
+            Dir::Right => x_1 += d,
+            Dir::Left => x_1 -= d,
         }
-        perimeter += d;
+        self.perimeter += d;
 
         // shoelace formula
-        area += (y + y_1) * (x - x_1);
-        y = y_1;
-        x = x_1;
+        let y = Checked::new(self.y, "dig") + y_1;
+        self.area = (y * (self.x - x_1) + self.area).get();
+        self.y = y_1;
+        self.x = x_1;
     }
 
-    area / 2 + perimeter / 2 + 1
+    fn size(&self) -> isize {
+        self.area / 2 + self.perimeter / 2 + 1
+    }
 }
 
-pub fn get_solution_1() -> usize {
-    let inp = parse_input(INPUT)
+fn picks_theorem(map: &[(isize, Dir)]) -> isize {
+    let mut lagoon = Lagoon::default();
+    for (d, dir) in map {
+        lagoon.dig(*d, *dir);
+    }
+    lagoon.size()
+}
+
+pub fn get_solution_1(input: &str) -> usize {
+    let inp = parse_input(input)
         .unwrap()
         .into_iter()
         .map(|DigEntry { steps, dir, .. }| (steps as isize, dir))
@@ -102,8 +123,8 @@ pub fn get_solution_1() -> usize {
     picks_theorem(&inp) as usize
 }
 
-pub fn get_solution_2() -> usize {
-    let inp = parse_input(INPUT)
+pub fn get_solution_2(input: &str) -> usize {
+    let inp = parse_input(input)
         .unwrap()
         .into_iter()
         .map(|DigEntry { color, .. }| parse_hex(color))
@@ -111,3 +132,104 @@ pub fn get_solution_2() -> usize {
 
     picks_theorem(&inp) as usize
 }
+
+// digs the lagoon along the entries of the plan, with the entry as (steps, direction)
+fn stream_lagoon(
+    reader: impl BufRead,
+    edge: impl Fn(DigEntry) -> (isize, Dir),
+) -> io::Result<usize> {
+    let mut lagoon = Lagoon::default();
+    for line in reader.lines() {
+        let line = line?;
+        let entry = DigEntry::try_from(line.as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let (d, dir) = edge(entry);
+        lagoon.dig(d, dir);
+    }
+    Ok(lagoon.size() as usize)
+}
+
+pub(crate) fn stream_solution_1(reader: impl BufRead) -> io::Result<usize> {
+    stream_lagoon(reader, |DigEntry { steps, dir, .. }| (steps as isize, dir))
+}
+
+pub(crate) fn stream_solution_2(reader: impl BufRead) -> io::Result<usize> {
+    stream_lagoon(reader, |DigEntry { color, .. }| parse_hex(color))
+}
+
+// a clockwise loop of `n_cols` columns with random widths and heights, as (direction, steps)
+fn generate_loop(rng: &mut Rng, n_cols: usize, max_len: usize) -> Vec<(char, usize)> {
+    let max_width = (max_len / n_cols).max(2);
+    let mut tops = vec![rng.range(0, max_len / 2)];
+    while tops.len() < n_cols {
+        let top = rng.range(0, max_len / 2);
+        if top != tops[tops.len() - 1] {
+            tops.push(top);
+        }
+    }
+    let bottom = max_len / 2 + rng.range(1, max_len / 2);
+
+    let mut plan = Vec::new();
+    let mut width = 0;
+    for (i, top) in tops.iter().enumerate() {
+        let w = rng.range(1, max_width);
+        width += w;
+        plan.push(('R', w));
+        match tops.get(i + 1) {
+            Some(next) if next < top => plan.push(('U', top - next)),
+            Some(next) => plan.push(('D', next - top)),
+            None => (),
+        }
+    }
+    plan.push(('D', bottom - tops[n_cols - 1]));
+    plan.push(('L', width));
+    plan.push(('U', bottom - tops[0]));
+
+    plan
+}
+
+/// Checks that every line has the form `<direction> <meters> (#<color>)`, where the color
+/// encodes the instruction of part 2
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        let [dir, meters, color] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            checker.error(line, "expected `<direction> <meters> (#<color>)`");
+            continue;
+        };
+        if !["U", "D", "L", "R"].contains(&dir) {
+            checker.error(dir, "expected `U`, `D`, `L` or `R`");
+        }
+        checker.parse::<usize>(meters, "a number of meters");
+
+        match color.strip_prefix("(#").and_then(|c| c.strip_suffix(')')) {
+            Some(hex) if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                checker.chars(&hex[5..], "0123")
+            }
+            _ => checker.error(color, "expected a color of six hex digits like `(#70c710)`"),
+        }
+    }
+    checker.finish()
+}
+
+/// Generates a plan with `size` columns, the loop encoded in the colors has the same number of
+/// columns but is a lot larger.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let small = generate_loop(rng, size, 20);
+    let large = generate_loop(rng, size, 0xfffff);
+
+    small
+        .into_iter()
+        .zip(large)
+        .map(|((dir, steps), (hex_dir, hex_steps))| {
+            let hex_dir = match hex_dir {
+                'R' => 0,
+                'D' => 1,
+                'L' => 2,
+                _ => 3,
+            };
+            format!("{dir} {steps} (#{hex_steps:05x}{hex_dir})\n")
+        })
+        .collect()
+}
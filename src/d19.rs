@@ -1,13 +1,22 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
     num::ParseIntError,
     ops::{Index, IndexMut},
 };
 
-#[allow(dead_code)]
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    explain::Explanation,
+    log::trace,
+    rng::Rng,
+};
+
 static TEST: &str = include_str!("../data/d19t");
-static INPUT: &str = include_str!("../data/d19");
+pub(crate) static INPUT: &str = include_str!("../data/d19");
+pub(crate) static TITLE: &str = "Aplenty";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1, 2])];
 
 type Workflows = HashMap<String, Vec<Rule>>;
 
@@ -253,12 +262,24 @@ impl Part {
     }
 
     fn sort<'a>(&self, workflows: &'a HashMap<String, Vec<Rule>>) -> &'a Destination {
-        let mut wf = &"in".to_string();
+        self.route(workflows).1
+    }
+
+    // the workflows the part goes through and where it ends up
+    fn route<'a>(
+        &self,
+        workflows: &'a HashMap<String, Vec<Rule>>,
+    ) -> (Vec<&'a str>, &'a Destination) {
+        let mut wf = "in";
+        let mut path = Vec::new();
         loop {
             if let Some(rules) = workflows.get(wf) {
-                wf = match self.apply_rules(rules) {
-                    Destination::Other(next) => &next,
-                    other => break other,
+                path.push(wf);
+                let dest = self.apply_rules(rules);
+                trace!("d19", "{self:?}: {wf} -> {dest:?}");
+                wf = match dest {
+                    Destination::Other(next) => next,
+                    other => break (path, other),
                 };
             }
         }
@@ -309,19 +330,15 @@ impl PartRange {
             match rule {
                 Rule::Dest(dest) => splits.push((self, dest)),
                 Rule::Eval(params) => {
-                    // see if we need to split, otherwise go to next
-                    // three possibilities:
-                    // 1. fully contained (1-x) < x + 1 || (x-4000) > x - 1, this does never seem
-                    //    to happen
-                    // 2. split: (1-y) < x && x < y
-                    // 3. not contained (y-x) < y || (y-x) > x
-                    if !self.contains(params.val, params.category) {
-                        continue;
-                    }
-                    // split
+                    // the range can be fully inside, fully outside or split by the rule
                     let [inside, outside] = self.split_at_category(params);
+                    if !inside.is_empty() {
+                        splits.push((inside, &params.dest));
+                    }
+                    if outside.is_empty() {
+                        break;
+                    }
                     self = outside;
-                    splits.push((inside, &params.dest));
                 }
             }
         }
@@ -337,6 +354,7 @@ impl PartRange {
             Comparison::Less => params.val,
         };
         let range = self[params.category];
+        let split = split.clamp(range.0, range.1);
 
         other[params.category] = (split, range.1);
         self[params.category] = (range.0, split);
@@ -347,13 +365,8 @@ impl PartRange {
         }
     }
 
-    fn contains(&self, split_idx: usize, category: Category) -> bool {
-        match category {
-            Category::X => self.x.0 <= split_idx && split_idx < self.x.1,
-            Category::M => self.m.0 <= split_idx && split_idx < self.m.1,
-            Category::A => self.a.0 <= split_idx && split_idx < self.a.1,
-            Category::S => self.s.0 <= split_idx && split_idx < self.s.1,
-        }
+    fn is_empty(&self) -> bool {
+        self.combinations() == 0
     }
 
     fn combinations(&self) -> usize {
@@ -364,13 +377,7 @@ impl PartRange {
     }
 }
 
-fn filter(workflows: Workflows) -> Vec<PartRange> {
-    let start = PartRange {
-        x: (1, 4001),
-        m: (1, 4001),
-        a: (1, 4001),
-        s: (1, 4001),
-    };
+fn filter(workflows: Workflows, start: PartRange) -> Vec<PartRange> {
     let dest = Destination::Other("in".to_string());
     let mut queue = Vec::from([(start, &dest)]);
     let mut accepted = Vec::new();
@@ -380,7 +387,11 @@ fn filter(workflows: Workflows) -> Vec<PartRange> {
             Destination::Accepted => accepted.push(range),
             Destination::Rejected => (),
             Destination::Other(name) => {
-                queue.append(&mut range.split(workflows.get(name).unwrap()))
+                let mut splits = range.split(workflows.get(name).unwrap());
+                for (split, dest) in &splits {
+                    trace!("d19", "{range:?}: {name} -> {dest:?} with {split:?}");
+                }
+                queue.append(&mut splits)
             }
         }
     }
@@ -402,8 +413,19 @@ fn parse_input(inp: &str) -> Result<(Workflows, Vec<Part>), InputError> {
     Ok((workflows, parts))
 }
 
-pub fn get_solution_1() -> usize {
-    match parse_input(INPUT) {
+/// The workflows sorted by name and the parts
+pub(crate) fn show(inp: &str) -> String {
+    match parse_input(inp) {
+        Ok((workflows, parts)) => {
+            let workflows: BTreeMap<_, _> = workflows.into_iter().collect();
+            format!("workflows: {workflows:#?}\nparts: {parts:#?}")
+        }
+        Err(e) => e.to_string(),
+    }
+}
+
+pub fn get_solution_1(input: &str) -> usize {
+    match parse_input(input) {
         Ok((workflows, parts)) => parts
             .into_iter()
             .filter(|p| p.sort(&workflows) == &Destination::Accepted)
@@ -416,12 +438,297 @@ pub fn get_solution_1() -> usize {
     }
 }
 
-pub fn get_solution_2() -> usize {
-    parse_input(INPUT).map_or_else(
+pub fn get_solution_2(input: &str) -> usize {
+    parse_input(input).map_or_else(
         |e| {
             println!("{e}");
             0
         },
-        |(wf, _)| filter(wf).into_iter().map(|r| r.combinations()).sum(),
+        |(wf, _)| {
+            let start = PartRange {
+                x: (1, 4001),
+                m: (1, 4001),
+                a: (1, 4001),
+                s: (1, 4001),
+            };
+            filter(wf, start)
+                .into_iter()
+                .map(|r| r.combinations())
+                .sum()
+        },
     )
 }
+
+/// A row per part with the workflows it went through for part 1, a row per range of accepted
+/// ratings for part 2
+pub(crate) fn explain(inp: &str, part: usize) -> Explanation {
+    let (workflows, parts) = match parse_input(inp) {
+        Ok(input) => input,
+        Err(e) => return Explanation::new(e.to_string(), &[]),
+    };
+
+    if part == 1 {
+        let mut explanation = Explanation::new(
+            "the sum of the ratings of the accepted parts",
+            &["part", "workflows", "result", "rating"],
+        );
+        for p in parts {
+            let (path, dest) = p.route(&workflows);
+            let (result, rating) = match dest {
+                Destination::Accepted => ("accepted", p.sum()),
+                _ => ("rejected", 0),
+            };
+            explanation.push(vec![
+                format!("{{x={},m={},a={},s={}}}", p.x, p.m, p.a, p.s).into(),
+                path.join(" -> ").into(),
+                result.into(),
+                rating.into(),
+            ]);
+        }
+        return explanation;
+    }
+
+    let mut explanation = Explanation::new(
+        "the sum of the combinations of the ranges of ratings which are accepted",
+        &["x", "m", "a", "s", "combinations"],
+    );
+    let start = PartRange {
+        x: (1, 4001),
+        m: (1, 4001),
+        a: (1, 4001),
+        s: (1, 4001),
+    };
+    let mut accepted = filter(workflows, start);
+    accepted.sort_by_key(|r| [r.x, r.m, r.a, r.s]);
+    for range in accepted {
+        let mut row: Vec<_> = [range.x, range.m, range.a, range.s]
+            .iter()
+            .map(|(lo, hi)| format!("{lo}-{}", hi - 1).into())
+            .collect();
+        row.push(range.combinations().into());
+        explanation.push(row);
+    }
+    explanation
+}
+
+/// Checks that the input has workflows of the form `<name>{<rule>,...,<destination>}`, which
+/// only send parts to defined workflows, followed by parts of the form `{x=..,m=..,a=..,s=..}`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let blocks = checker.blocks();
+    for line in blocks.iter().skip(2).flatten() {
+        checker.error(line, "expected only workflows and parts");
+    }
+    let workflows = blocks.first().map_or(&[][..], |b| &b[..]);
+    let parts = blocks.get(1).map_or(&[][..], |b| &b[..]);
+
+    let mut names = HashSet::new();
+    let mut destinations = Vec::new();
+    for line in workflows {
+        let Some((name, rules)) = checker.split_once(line, "{") else {
+            continue;
+        };
+        let Some(rules) = rules.strip_suffix('}') else {
+            checker.error_after(line, "expected `}`");
+            continue;
+        };
+        names.insert(name);
+
+        let rules: Vec<_> = rules.split(',').collect();
+        let (last, conditions) = rules.split_last().unwrap();
+        for rule in conditions {
+            let Some((condition, dest)) = checker.split_once(rule, ":") else {
+                continue;
+            };
+            let mut chars = condition.chars();
+            match (chars.next(), chars.next()) {
+                (Some('x' | 'm' | 'a' | 's'), Some('<' | '>')) => {
+                    checker.parse::<usize>(&condition[2..], "a rating");
+                }
+                _ => checker.error(condition, "expected a condition like `x<100`"),
+            }
+            destinations.push(dest);
+        }
+        destinations.push(last);
+    }
+
+    if !names.contains("in") {
+        if let Some(first) = workflows.first() {
+            checker.error(&first[..0], "expected a workflow named `in`");
+        }
+    }
+    for dest in destinations {
+        if !["A", "R"].contains(&dest) && !names.contains(dest) {
+            checker.error(dest, format!("unknown workflow `{dest}`"));
+        }
+    }
+
+    for line in parts {
+        let Some(ratings) = line.strip_prefix('{').and_then(|p| p.strip_suffix('}')) else {
+            checker.error(line, "expected `{x=..,m=..,a=..,s=..}`");
+            continue;
+        };
+        let ratings: Vec<_> = ratings.split(',').collect();
+        if ratings.len() != 4 {
+            checker.error(line, "expected four ratings");
+            continue;
+        }
+        for (rating, category) in ratings.into_iter().zip(["x=", "m=", "a=", "s="]) {
+            match rating.strip_prefix(category) {
+                Some(value) => _ = checker.parse::<usize>(value, "a rating"),
+                None => checker.error(rating, format!("expected `{category}<rating>`")),
+            }
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` workflows and `size` parts, workflows only send parts to workflows defined
+/// after them, so there are no cycles
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut names = vec!["in".to_string()];
+    while names.len() < size {
+        let len = rng.range(2, 4);
+        let name = rng.word(len);
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut inp = String::new();
+    for (i, name) in names.iter().enumerate() {
+        let dest = |rng: &mut Rng| match rng.range(0, 4) {
+            0 => "A".to_string(),
+            1 => "R".to_string(),
+            _ if i + 1 < size => names[rng.range(i + 1, size)].clone(),
+            _ => "R".to_string(),
+        };
+        let mut rules: Vec<String> = (0..rng.range(1, 5))
+            .map(|_| {
+                format!(
+                    "{}{}{}:{}",
+                    rng.pick(&['x', 'm', 'a', 's']),
+                    rng.pick(&['<', '>']),
+                    rng.range(1, 4001),
+                    dest(rng)
+                )
+            })
+            .collect();
+        rules.push(dest(rng));
+        inp.push_str(&format!("{name}{{{}}}\n", rules.join(",")));
+    }
+
+    inp.push('\n');
+    for _ in 0..size {
+        let [x, m, a, s] = [(); 4].map(|_| rng.range(1, 4001));
+        inp.push_str(&format!("{{x={x},m={m},a={a},s={s}}}\n"));
+    }
+
+    inp
+}
+
+#[test]
+fn test_range_inside_rule() {
+    // the ranges reaching `a` lie entirely inside its rule, they used to skip it and be rejected
+    assert_eq!(
+        get_solution_2("in{x<2000:a,R}\na{x<3000:A,R}\n\n"),
+        1999 * 4000 * 4000 * 4000
+    );
+    assert_eq!(
+        get_solution_2("in{m>1000:a,R}\na{m>500:A,R}\n\n"),
+        3000 * 4000 * 4000 * 4000
+    );
+}
+
+#[test]
+fn test_filter_matches_brute_force() {
+    use crate::util::prop::{self, shrink_each, shrink_vec};
+
+    // keep the ratings small, so every part can be sorted
+    const MAX: usize = 6;
+
+    // workflows as (name, rules, fallback), workflows only send parts to later workflows
+    prop::check(
+        "filter",
+        |rng, size| {
+            let n = rng.range(1, size.min(8) + 1);
+            let names: Vec<String> = (0..n).map(|i| format!("w{i}")).collect();
+            (0..n)
+                .map(|i| {
+                    let dest = |rng: &mut Rng| match rng.range(0, 3) {
+                        0 => "A".to_string(),
+                        1 if i + 1 < n => names[rng.range(i + 1, n)].clone(),
+                        _ => "R".to_string(),
+                    };
+                    let rules: Vec<String> = (0..rng.range(0, 4))
+                        .map(|_| {
+                            format!(
+                                "{}{}{}:{}",
+                                rng.pick(&['x', 'm', 'a', 's']),
+                                rng.pick(&['<', '>']),
+                                rng.range(1, MAX + 1),
+                                dest(rng)
+                            )
+                        })
+                        .collect();
+                    (names[i].clone(), rules, dest(rng))
+                })
+                .collect::<Vec<_>>()
+        },
+        |workflows| {
+            shrink_each(workflows, |(name, rules, fallback)| {
+                shrink_vec(rules)
+                    .into_iter()
+                    .map(|rules| (name.clone(), rules, fallback.clone()))
+                    .collect()
+            })
+        },
+        |workflows| {
+            let inp: String = workflows
+                .iter()
+                .map(|(name, rules, fallback)| {
+                    let name = if name == "w0" { "in" } else { name };
+                    let rules = rules.iter().chain([fallback]).cloned();
+                    format!("{name}{{{}}}\n", rules.collect::<Vec<_>>().join(","))
+                })
+                .collect::<String>()
+                .replace(":w0", ":in");
+            let (workflows, _) = parse_input(&inp).map_err(|e| e.to_string())?;
+
+            let mut expected = 0;
+            for [x, m, a, s] in
+                (0..MAX.pow(4)).map(|i| [0, 1, 2, 3].map(|d| 1 + i / MAX.pow(d) % MAX))
+            {
+                if (Part { x, m, a, s }).sort(&workflows) == &Destination::Accepted {
+                    expected += 1;
+                }
+            }
+
+            let start = PartRange {
+                x: (1, MAX + 1),
+                m: (1, MAX + 1),
+                a: (1, MAX + 1),
+                s: (1, MAX + 1),
+            };
+            let actual = filter(workflows, start)
+                .into_iter()
+                .map(|r| r.combinations())
+                .sum::<usize>();
+            prop::assert_eq(actual, expected)
+        },
+    );
+}
+
+#[test]
+fn test_explain() {
+    use crate::util::explain::Format;
+
+    let explanation = explain(TEST, 1).write(Format::Json);
+    assert!(explanation
+        .contains(r#""workflows":"in -> qqz -> qs -> lnx","result":"accepted","rating":7540"#));
+    let explanation = explain(TEST, 2).write(Format::Json);
+    assert!(explanation.contains(
+        r#"{"x":"1-1415","m":"1-4000","a":"1-2005","s":"1-1350","combinations":15320205000000}"#
+    ));
+}
@@ -1,23 +1,41 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    bigint::BigUint,
+    cancel,
+    diag::{Checker, Diagnostic},
+    graph::{Format, Graph},
+    log::{debug, trace},
+    rng::Rng,
+};
 
-#[allow(dead_code)]
 static TEST: &str = include_str!("../data/d20t");
 
-#[allow(dead_code)]
 static TEST2: &str = include_str!("../data/d20t2");
 
-static INPUT: &str = include_str!("../data/d20");
+pub(crate) static INPUT: &str = include_str!("../data/d20");
+pub(crate) static TITLE: &str = "Pulse Propagation";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1]), Example::new(TEST2, &[1])];
+
+pub(crate) static PARAMS: &[Param] = &[Param {
+    name: "presses",
+    default: "1000",
+    validate: params::count,
+    help: "button presses in part 1",
+}];
 
-type HMGraph = HashMap<&'static str, Node>;
+type HMGraph<'a> = HashMap<&'a str, Node<'a>>;
 
-struct Node {
-    typ: ModuleTypeMem,
+struct Node<'a> {
+    typ: ModuleTypeMem<'a>,
     last_pulse: bool,
-    children: Vec<&'static str>,
+    children: Vec<&'a str>,
 }
 
-impl Node {
-    fn new(name: &str, children: Vec<&'static str>) -> Self {
+impl<'a> Node<'a> {
+    fn new(name: &str, children: Vec<&'a str>) -> Self {
         let typ = match name {
             n if n == "broadcaster" => ModuleTypeMem::Broadcast,
             n if n.starts_with("&") => ModuleTypeMem::Conjunction(HashMap::new()),
@@ -35,14 +53,14 @@ impl Node {
 }
 
 #[derive(Debug, Clone)]
-enum ModuleTypeMem {
+enum ModuleTypeMem<'a> {
     FlipFlop(bool),
-    Conjunction(HashMap<&'static str, bool>),
+    Conjunction(HashMap<&'a str, bool>),
     Broadcast,
     Out,
 }
 
-impl ModuleTypeMem {
+impl ModuleTypeMem<'_> {
     fn handle_pulse(&mut self, pulse: bool, parent: &str) -> Option<bool> {
         let pulse_to_send = match self {
             ModuleTypeMem::FlipFlop(cur) => match pulse {
@@ -63,7 +81,7 @@ impl ModuleTypeMem {
     }
 }
 
-fn parse_input(inp: &'static str) -> HMGraph {
+fn parse_input(inp: &str) -> HMGraph<'_> {
     let mut graph = HashMap::new();
     // determine modules types and children
     for line in inp.lines() {
@@ -80,6 +98,22 @@ fn parse_input(inp: &'static str) -> HMGraph {
     }
     // insert the 'rx' module
     graph.insert("rx", Node::new("rx", Vec::new()));
+    // and the other untyped modules, like 'output' in the examples, which only receive pulses too
+    let untyped: Vec<_> = graph
+        .values()
+        .flat_map(|n| n.children.iter().copied())
+        .filter(|c| !graph.contains_key(c))
+        .collect();
+    for name in untyped {
+        graph.insert(
+            name,
+            Node {
+                typ: ModuleTypeMem::Out,
+                last_pulse: false,
+                children: Vec::new(),
+            },
+        );
+    }
 
     // determine the parents of the modules
     for line in inp.lines() {
@@ -99,12 +133,36 @@ fn parse_input(inp: &'static str) -> HMGraph {
     graph
 }
 
-pub fn get_solution_1() -> usize {
-    let mut g = parse_input(INPUT);
+/// The modules as a graph, with an edge to every module a module sends its pulses to
+pub(crate) fn export_graph(inp: &str, format: Format) -> String {
+    let modules = parse_input(inp);
+    let mut names: Vec<_> = modules.keys().copied().collect();
+    names.sort_unstable();
+
+    let mut graph = Graph::directed();
+    for name in names {
+        graph.node(name);
+        for child in &modules[name].children {
+            graph.add_edge(name, child, ());
+        }
+    }
+    graph.write(format)
+}
+
+// the name of a pulse in the log, like in the puzzle
+fn pulse_name(pulse: bool) -> &'static str {
+    match pulse {
+        true => "high",
+        false => "low",
+    }
+}
+
+pub fn get_solution_1(input: &str, params: &Params) -> usize {
+    let mut g = parse_input(input);
     let mut low_pulses = 0;
     let mut high_pulses = 0;
 
-    for _ in 0..1000 {
+    for _ in 0..params.get::<usize>("presses") {
         let mut queue = VecDeque::from([("broadcaster", false, "dummy")]);
 
         while let Some((cur, pulse, parent)) = queue.pop_front() {
@@ -112,6 +170,7 @@ pub fn get_solution_1() -> usize {
                 true => high_pulses += 1,
                 false => low_pulses += 1,
             }
+            trace!("d20", "{parent} -{}-> {cur}", pulse_name(pulse));
 
             let node = g.get_mut(cur).unwrap();
 
@@ -129,15 +188,17 @@ pub fn get_solution_1() -> usize {
     high_pulses * low_pulses
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    let mut g = parse_input(INPUT);
+pub(crate) fn get_solution_2(input: &str) -> BigUint {
+    let mut g = parse_input(input);
     /* these could be determined programmaticly by looking at the parents */
     let mut conj = HashMap::from([("br", None), ("lf", None), ("rz", None), ("fk", None)]);
 
-    for i in 1.. {
+    for i in 1_usize.. {
+        cancel::check();
         let mut queue = VecDeque::from([("broadcaster", false, "dummy")]);
 
         while let Some((cur, pulse, parent)) = queue.pop_front() {
+            trace!("d20", "{parent} -{}-> {cur}", pulse_name(pulse));
             let node = g.get_mut(cur).unwrap();
 
             node.last_pulse = match node.typ.handle_pulse(pulse, parent) {
@@ -146,7 +207,10 @@ pub(crate) fn get_solution_2() -> usize {
             };
 
             match conj.iter().find(|(m, _)| **m == cur) {
-                Some((_, None)) if node.last_pulse => *conj.get_mut(cur).unwrap() = Some(i),
+                Some((_, None)) if node.last_pulse => {
+                    debug!("d20", "{cur} sends a high pulse after {i} presses");
+                    *conj.get_mut(cur).unwrap() = Some(i)
+                }
                 _ => (),
             }
 
@@ -160,5 +224,79 @@ pub(crate) fn get_solution_2() -> usize {
             break;
         }
     }
-    conj.values().filter_map(|v| *v).product::<usize>()
+    conj.values()
+        .filter_map(|v| *v)
+        .map(BigUint::from)
+        .product::<BigUint>()
+}
+
+/// Checks that every line has the form `<module> -> <destination>, ...`, where destinations are
+/// defined modules or `rx`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let mut modules = HashSet::from(["rx"]);
+    let mut destinations = Vec::new();
+    let lines = checker.lines();
+
+    for line in &lines {
+        let Some((module, children)) = checker.split_once(line, "->") else {
+            continue;
+        };
+        let module = module.trim();
+        let name = module.trim_start_matches(['%', '&']);
+        if name.is_empty() || (name == module && name != "broadcaster") {
+            checker.error(module, "expected `broadcaster`, `%<name>` or `&<name>`");
+        }
+        modules.insert(name);
+        destinations.extend(children.split(',').map(|c| c.trim()));
+    }
+
+    if !modules.contains("broadcaster") {
+        if let Some(first) = lines.first() {
+            checker.error(&first[..0], "expected a `broadcaster`");
+        }
+    }
+    for dest in destinations {
+        if !modules.contains(dest) {
+            checker.error(dest, format!("unknown module `{dest}`"));
+        }
+    }
+    checker.finish()
+}
+
+/// Generates four binary counters made of flip-flops, feeding into the conjunctions `br`, `lf`,
+/// `rz` and `fk` in front of `rx`. Each counter has between `size / 2` and `size` bits (at most
+/// 20).
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(2, 20);
+    let mut names = HashSet::from(["br", "lf", "rz", "fk", "rx"].map(String::from));
+    let mut name = |rng: &mut Rng| loop {
+        let name = rng.word(2);
+        if names.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    let mut modules = Vec::new();
+    let mut firsts = Vec::new();
+    for inverter in ["br", "lf", "rz", "fk"] {
+        let bits: Vec<String> = (0..rng.range(size / 2 + 1, size + 1))
+            .map(|_| name(rng))
+            .collect();
+        let counter = name(rng);
+        for (i, bit) in bits.iter().enumerate() {
+            match bits.get(i + 1) {
+                Some(next) => modules.push(format!("%{bit} -> {next}, {counter}")),
+                None => modules.push(format!("%{bit} -> {counter}")),
+            }
+        }
+        modules.push(format!("&{counter} -> {inverter}"));
+        modules.push(format!("&{inverter} -> hub"));
+        firsts.push(bits[0].clone());
+    }
+    modules.push("&hub -> rx".to_string());
+    modules.push(format!("broadcaster -> {}", firsts.join(", ")));
+    rng.shuffle(&mut modules);
+
+    modules.into_iter().map(|m| m + "\n").collect()
 }
@@ -1,10 +1,37 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::VecDeque;
 use std::fmt::Display;
 use std::ops::Index;
 
-#[allow(unused)]
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    bigint::BigUint,
+    bits::BitGrid,
+    diag::{Checker, Diagnostic},
+    hash::{FastMap, FastSet},
+    rng::Rng,
+    scale,
+};
+
 static TEST: &str = include_str!("../data/d21t");
-static INPUT: &str = include_str!("../data/d21");
+pub(crate) static INPUT: &str = include_str!("../data/d21");
+pub(crate) static TITLE: &str = "Step Counter";
+pub(crate) static EXAMPLES: &[Example] = &[Example::new(TEST, &[1]).params(&[("steps", "6")])];
+
+pub(crate) static PARAMS: &[Param] = &[
+    Param {
+        name: "steps",
+        default: "64",
+        validate: params::count,
+        help: "steps of the elf in part 1",
+    },
+    Param {
+        name: "infinite_steps",
+        default: "26501365",
+        validate: params::count,
+        help: "steps of the elf in part 2, has to be half the map size plus an even multiple of it",
+    },
+];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Tile {
@@ -51,7 +78,7 @@ impl Display for Map {
 }
 
 impl Map {
-    fn get_next_positions(&self, (x, y): (usize, usize)) -> HashSet<(usize, usize)> {
+    fn get_next_positions(&self, (x, y): (usize, usize)) -> FastSet<(usize, usize)> {
         [
             (x + 1, y),
             (x.wrapping_sub(1), y),
@@ -66,6 +93,18 @@ impl Map {
         .collect()
     }
 
+    fn gardens(&self) -> BitGrid {
+        let mut gardens = BitGrid::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self[(x, y)] == Tile::Garden {
+                    gardens.insert(x, y);
+                }
+            }
+        }
+        gardens
+    }
+
     fn get_tile(&self, (x, y): (usize, usize)) -> Option<&Tile> {
         if x >= self.width || y >= self.height {
             return None;
@@ -114,32 +153,22 @@ fn parse_input(input: &str) -> ((usize, usize), Map) {
 }
 
 fn calculate_reachable_number_of_tiles(start: (usize, usize), map: Map, n_steps: usize) -> usize {
-    let mut even = HashSet::from([start]);
-    let mut odd = HashSet::from([]);
-
-    for i in 0..n_steps {
-        let (cur, next) = if i % 2 == 0 {
-            (&even, &mut odd)
-        } else {
-            (&odd, &mut even)
-        };
-        for pos in cur {
-            for pos in map.get_next_positions(*pos) {
-                next.insert(pos);
-            }
-        }
-    }
+    // the elf can always step back and forth, so the tiles reachable in exactly `n_steps` are
+    // the ones reachable in any number of steps with the same parity
+    let gardens = map.gardens();
+    let mut cur = BitGrid::new(map.width, map.height);
+    cur.insert(start.0, start.1);
 
-    if n_steps % 2 == 0 {
-        even.len()
-    } else {
-        odd.len()
+    for _ in 0..n_steps {
+        cur = &cur.neighbours() & &gardens;
     }
+
+    cur.count()
 }
 
-fn get_shortest_paths_per_tile(start: (usize, usize), map: Map) -> HashMap<(usize, usize), usize> {
+fn get_shortest_paths_per_tile(start: (usize, usize), map: Map) -> FastMap<(usize, usize), usize> {
     let mut queue = VecDeque::from([(start, 0)]);
-    let mut seen = HashMap::new();
+    let mut seen = FastMap::default();
 
     while let Some((pos, dist)) = queue.pop_front() {
         if seen.contains_key(&pos) {
@@ -161,15 +190,19 @@ fn get_shortest_paths_per_tile(start: (usize, usize), map: Map) -> HashMap<(usiz
 * Taken from https://github.com/villuna/aoc23/wiki/A-Geometric-solution-to-advent-of-code-2023,-day-21
 * which contains a very good explanation on how this works
 */
-fn calculate_visited_tiles(visited_tiles: HashMap<(usize, usize), usize>, dim: usize) -> usize {
+fn calculate_visited_tiles(
+    visited_tiles: FastMap<(usize, usize), usize>,
+    dim: usize,
+    n_steps: usize,
+) -> BigUint {
     let even_corners = visited_tiles
         .values()
-        .filter(|steps| **steps % 2 == 0 && **steps > 65)
+        .filter(|steps| **steps % 2 == 0 && **steps > dim / 2)
         .count();
 
     let odd_corners = visited_tiles
         .values()
-        .filter(|steps| **steps % 2 == 1 && **steps > 65)
+        .filter(|steps| **steps % 2 == 1 && **steps > dim / 2)
         .count();
 
     let even_full = visited_tiles
@@ -182,20 +215,166 @@ fn calculate_visited_tiles(visited_tiles: HashMap<(usize, usize), usize>, dim: u
         .filter(|steps| **steps % 2 == 1)
         .count();
 
-    let n_squares = (26501365 - (dim / 2)) / dim;
+    let [odd_full, even_full, odd_corners, even_corners] =
+        [odd_full, even_full, odd_corners, even_corners].map(BigUint::from);
+    let n_squares = BigUint::from((n_steps - (dim / 2)) / dim);
+    let n_squares_1 = &n_squares + BigUint::from(1u32);
 
-    (n_squares + 1).pow(2) * odd_full + n_squares.pow(2) * even_full - (n_squares + 1) * odd_corners
-        + n_squares * even_corners
+    &n_squares_1 * &n_squares_1 * odd_full
+        + &n_squares * &n_squares * even_full
+        + &n_squares * even_corners
+        - n_squares_1 * odd_corners
 }
 
-pub fn get_solution_1() -> usize {
-    let (start, map) = parse_input(INPUT);
-    calculate_reachable_number_of_tiles(start, map, 64)
+pub fn get_solution_1(input: &str, params: &Params) -> usize {
+    let (start, map) = parse_input(input);
+    calculate_reachable_number_of_tiles(start, map, params.get("steps"))
 }
 
-pub fn get_solution_2() -> usize {
-    let (start, map) = parse_input(INPUT);
+pub fn get_solution_2(input: &str, params: &Params) -> Result<BigUint, String> {
+    let (start, map) = parse_input(input);
     let dim = map.height;
+    // the counting only works when the steps end on the edge of a map, with as many maps on
+    // either side of the first one
+    let n_steps: usize = params.get("infinite_steps");
+    if n_steps < dim / 2 || !(n_steps - dim / 2).is_multiple_of(2 * dim) {
+        return Err(format!(
+            "infinite_steps has to be {} plus a multiple of {}, not {n_steps}",
+            dim / 2,
+            2 * dim
+        ));
+    }
     let shortest_paths_per_tile = get_shortest_paths_per_tile(start, map);
-    calculate_visited_tiles(shortest_paths_per_tile, dim)
+    Ok(calculate_visited_tiles(
+        shortest_paths_per_tile,
+        dim,
+        n_steps,
+    ))
+}
+
+/// Checks that the map is a square of gardens and rocks with a single start
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines = checker.lines();
+    checker.grid(&lines, ".#S");
+    checker.single(&lines, 'S');
+    if lines.first().is_some_and(|l| l.len() != lines.len()) {
+        checker.error(lines[0], "expected a square map");
+    }
+    checker.finish()
+}
+
+/// Generates a `size` x `size` map (rounded up to the next odd number), with the start in the
+/// center. The center row and column as well as the border are kept free of rocks.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5) | 1;
+    let center = size / 2;
+    let mut inp = String::new();
+
+    for y in 0..size {
+        inp.extend((0..size).map(|x| {
+            if (x, y) == (center, center) {
+                'S'
+            } else if [x, y].iter().any(|c| [0, center, size - 1].contains(c)) || !rng.chance(10) {
+                '.'
+            } else {
+                '#'
+            }
+        }));
+        inp.push('\n');
+    }
+
+    inp
+}
+
+/// Tiles the map, only the center tile keeps the start, so the start stays in the center of
+/// the map as part 2 needs
+pub(crate) fn scale(inp: &str, factor: usize) -> Result<String, String> {
+    if factor.is_multiple_of(2) {
+        return Err("the factor has to be odd to keep the start in the center".into());
+    }
+    let center = (factor / 2, factor / 2);
+    Ok(scale::tile(inp, factor, |t, c| match c {
+        'S' if t != center => '.',
+        c => c,
+    }))
+}
+
+#[cfg(test)]
+fn calculate_reachable_tiles_infinite(start: (usize, usize), map: &Map, n_steps: usize) -> usize {
+    let (w, h) = (map.width as isize, map.height as isize);
+    let mut cur = FastSet::from_iter([(start.0 as isize, start.1 as isize)]);
+
+    for _ in 0..n_steps {
+        cur = cur
+            .iter()
+            .flat_map(|&(x, y)| [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)])
+            .filter(|&(x, y)| {
+                map[(x.rem_euclid(w) as usize, y.rem_euclid(h) as usize)] == Tile::Garden
+            })
+            .collect();
+    }
+
+    cur.len()
+}
+
+// counts the gardens with the same parity as `n_steps` within a manhattan distance of `n_steps`,
+// which is the number of reachable tiles if rocks never force a detour
+#[cfg(test)]
+fn count_gardens_within(start: (usize, usize), map: &Map, n_steps: usize) -> usize {
+    let (w, h, n) = (map.width as isize, map.height as isize, n_steps as isize);
+    let (sx, sy) = (start.0 as isize, start.1 as isize);
+
+    (-n..=n)
+        .flat_map(|dy| (-n..=n).map(move |dx| (dx, dy)))
+        .filter(|(dx, dy)| dx.abs() + dy.abs() <= n && (dx.abs() + dy.abs()) % 2 == n % 2)
+        .filter(|(dx, dy)| {
+            let pos = ((sx + dx).rem_euclid(w), (sy + dy).rem_euclid(h));
+            map[(pos.0 as usize, pos.1 as usize)] == Tile::Garden
+        })
+        .count()
+}
+
+#[test]
+fn test_calculate_visited_tiles_matches_brute_force() {
+    use crate::util::prop;
+
+    // the geometric solution relies on the same shape as the real input: an odd number of steps
+    // reaching an even number of maps past the first one, so only those cases are generated.
+    // It also assumes that rocks never force a detour, neither within a map nor across maps,
+    // maps where they do are skipped.
+    prop::check(
+        "calculate_visited_tiles",
+        |rng, size| {
+            let dim = *rng.pick(&[7, 11, 15][..(size / 8 + 1).min(3)]);
+            let n_squares = *rng.pick(&[2, 4]);
+            (generate(rng, dim), dim / 2 + n_squares * dim)
+        },
+        |_| Vec::new(),
+        |(inp, n_steps)| {
+            let (start, map) = parse_input(inp);
+            let expected = calculate_reachable_tiles_infinite(start, &map, *n_steps);
+            let paths = get_shortest_paths_per_tile(start, map.clone());
+            let detour = paths
+                .iter()
+                .any(|(pos, steps)| pos.0.abs_diff(start.0) + pos.1.abs_diff(start.1) != *steps);
+            if detour || expected != count_gardens_within(start, &map, *n_steps) {
+                return Ok(());
+            }
+            let dim = map.height;
+            let actual = calculate_visited_tiles(paths, dim, *n_steps);
+            prop::assert_eq(actual, BigUint::from(expected))
+        },
+    );
+}
+
+#[test]
+fn test_infinite_steps_off_the_edge() {
+    let mut params = Params::new(PARAMS);
+    for steps in ["10", "100", "196"] {
+        params.set("infinite_steps", steps).unwrap();
+        assert!(get_solution_2(INPUT, &params).is_err(), "{steps} steps");
+    }
+    params.set("infinite_steps", "327").unwrap();
+    assert!(get_solution_2(INPUT, &params).is_ok());
 }
@@ -1,8 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::days::Example;
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    frames,
+    log::trace,
+    rng::Rng,
+};
 
 #[allow(unused)]
 static TEST: &str = include_str!("../data/d22t");
-static INPUT: &str = include_str!("../data/d22");
+pub(crate) static INPUT: &str = include_str!("../data/d22");
+pub(crate) static TITLE: &str = "Sand Slabs";
+// data/d22t is empty, so there is no example to run
+pub(crate) static EXAMPLES: &[Example] = &[];
 
 static X: usize = 0;
 static Y: usize = 1;
@@ -113,6 +124,35 @@ fn parse_input(inp: &str) -> Vec<Brick> {
     bricks
 }
 
+/// One brick per line, in the order of the input
+pub(crate) fn show(inp: &str) -> String {
+    parse_input(inp)
+        .iter()
+        .map(|brick| format!("{brick:?}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// renders the bricks as seen from the front, looking along the y axis
+fn render(bricks: &[Brick]) -> String {
+    let width = bricks.iter().map(|b| b.end[X] + 1).max().unwrap_or(0);
+    let height = bricks.iter().map(|b| b.end[Z] + 1).max().unwrap_or(0);
+    let mut rows = vec![vec!['.'; width]; height];
+
+    for b in bricks {
+        for row in &mut rows[b.start[Z]..=b.end[Z]] {
+            row[b.start[X]..=b.end[X]].fill('#');
+        }
+    }
+
+    rows.iter()
+        .skip(1)
+        .rev()
+        .map(|row| row.iter().chain(['\n'].iter()).collect::<String>())
+        .chain([format!("{}\n", "-".repeat(width))])
+        .collect()
+}
+
 fn drop_bricks(mut bricks: Vec<Brick>) -> Vec<Brick> {
     // sort bricks to according to their z position
     bricks.sort_by(|a, b| a.start[Z].cmp(&b.start[Z]));
@@ -120,7 +160,7 @@ fn drop_bricks(mut bricks: Vec<Brick>) -> Vec<Brick> {
     let mut dropped_bricks = Vec::new();
 
     for mut brick in bricks {
-        //println!("{brick:?}");
+        trace!("d22", "dropping {brick:?}");
         let max_z = dropped_bricks
             .iter()
             .filter(|b| brick.overlaps(b, X) && brick.overlaps(b, Y))
@@ -133,6 +173,7 @@ fn drop_bricks(mut bricks: Vec<Brick>) -> Vec<Brick> {
         };
         assert!(!dropped_bricks.contains(&brick));
         dropped_bricks.push(brick);
+        frames::emit("d22", || render(&dropped_bricks));
     }
 
     dropped_bricks
@@ -180,16 +221,16 @@ fn drop_bricks_counted(mut bricks: Vec<Brick>, end_z: usize) -> usize {
     count
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    let dropped_bricks = drop_bricks(parse_input(INPUT));
+pub(crate) fn get_solution_1(input: &str) -> usize {
+    let dropped_bricks = drop_bricks(parse_input(input));
     dropped_bricks
         .iter()
         .filter(|b| b.safe_to_disintegrate(&dropped_bricks))
         .count()
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    let dropped_bricks = drop_bricks(parse_input(INPUT));
+pub(crate) fn get_solution_2(input: &str) -> usize {
+    let dropped_bricks = drop_bricks(parse_input(input));
     dropped_bricks
         .iter()
         .filter(|b| !b.safe_to_disintegrate(&dropped_bricks))
@@ -197,14 +238,86 @@ pub(crate) fn get_solution_2() -> usize {
         .sum()
 }
 
+/// Checks that every line has the form `x,y,z~x,y,z`, with the start before the end
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        let Some((start, end)) = checker.split_once(line, "~") else {
+            continue;
+        };
+        let [start, end] = [start, end].map(|point| {
+            let coords: Vec<_> = point.split(',').collect();
+            if coords.len() != 3 {
+                checker.error(point, "expected three coordinates `x,y,z`");
+            }
+            coords
+                .into_iter()
+                .map(|c| checker.parse::<usize>(c, "a coordinate"))
+                .collect::<Option<Vec<_>>>()
+        });
+        if let (Some(start), Some(end)) = (start, end) {
+            if start.iter().zip(&end).any(|(s, e)| s > e) {
+                checker.error(line, "expected the start to be before the end");
+            }
+        }
+    }
+    checker.finish()
+}
+
+/// Generates a snapshot of `size` bricks in a 10 x 10 area, about a third of them vertical
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut occupied = HashSet::new();
+    let mut inp = String::new();
+    let mut placed = 0;
+
+    while placed < size {
+        let axis = match rng.range(0, 3) {
+            0 => Z,
+            _ => *rng.pick(&[X, Y]),
+        };
+        let mut start = [
+            rng.range(0, 10),
+            rng.range(0, 10),
+            rng.range(1, 2 * size + 2),
+        ];
+        let mut end = start;
+        end[axis] += rng.range(0, 4);
+        // keep the bricks inside the area
+        if axis != Z && end[axis] > 9 {
+            start[axis] -= end[axis] - 9;
+            end[axis] = 9;
+        }
+
+        let cubes: Vec<_> = (start[axis]..=end[axis])
+            .map(|i| {
+                let mut cube = start;
+                cube[axis] = i;
+                cube
+            })
+            .collect();
+        if cubes.iter().any(|c| occupied.contains(c)) {
+            continue;
+        }
+        occupied.extend(cubes);
+
+        inp.push_str(&format!(
+            "{},{},{}~{},{},{}\n",
+            start[X], start[Y], start[Z], end[X], end[Y], end[Z]
+        ));
+        placed += 1;
+    }
+
+    inp
+}
+
 #[test]
 fn test_get_solution_1() {
-    println!("{}", get_solution_1());
+    println!("{}", get_solution_1(INPUT));
 }
 
 #[test]
 fn test_get_solution_2() {
-    println!("{}", get_solution_2());
+    println!("{}", get_solution_2(INPUT));
 }
 
 #[test]
@@ -3,9 +3,22 @@ use std::{
     fmt::Display,
 };
 
+use crate::days::Example;
+use crate::util::{
+    cancel,
+    diag::{Checker, Diagnostic},
+    graph::{Format, Graph},
+    hash::FastMap,
+    progress::{self, Progress},
+    rng::Rng,
+};
+
 #[allow(unused)]
 static TEST: &str = include_str!("../data/d23t");
-static INPUT: &str = include_str!("../data/d23");
+pub(crate) static INPUT: &str = include_str!("../data/d23");
+pub(crate) static TITLE: &str = "A Long Walk";
+// data/d23t is empty, so there is no example to run
+pub(crate) static EXAMPLES: &[Example] = &[];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Dir {
@@ -83,6 +96,12 @@ struct Point {
     y: usize,
 }
 
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
 impl Point {
     fn update_position(&self, dir: Dir) -> Self {
         match dir {
@@ -284,46 +303,55 @@ fn follow_path(
     follow_path(map, next, dir, visited)
 }
 
-fn bfs(start: Point, end: Point, graph: HashMap<Point, HashSet<(Point, usize)>>) -> usize {
-    /* Visited points are encoded into a 64 bit integer */
-    let mut costs = Vec::new();
-    let mut queue = VecDeque::from([(start, 0, 0)]);
-    let mut longest_so_far: HashMap<(Point, usize), usize> = HashMap::new();
-    /* give each point a unique bitmask */
-    let point_to_usize_table: HashMap<_, _> = graph
-        .iter()
-        .flat_map(|(parent, points)| {
-            let mut points = points.iter().copied().map(|(p, _)| p).collect::<Vec<_>>();
-            points.push(*parent);
-            points
-        })
-        .collect::<HashSet<_>>()
+// the compacted edges as a graph of the junctions, named `x,y`, weighted with the lengths of the
+// paths between them
+fn to_graph(edges: HashMap<Point, HashSet<(Point, usize)>>) -> Graph<usize> {
+    let mut edges: Vec<_> = edges
         .into_iter()
-        .enumerate()
-        .map(|(i, p)| (p, 1 << i))
+        .flat_map(|(from, to)| to.into_iter().map(move |(to, cost)| (from, to, cost)))
         .collect();
+    edges.sort_unstable_by_key(|(from, to, _)| ((from.y, from.x), (to.y, to.x)));
+
+    let mut graph = Graph::directed();
+    for (from, to, cost) in edges {
+        graph.add_edge(&from.to_string(), &to.to_string(), cost);
+    }
+    graph
+}
 
-    while let Some((point, cost, mut visited)) = queue.pop_front() {
-        let point_as_usize = point_to_usize_table.get(&point).unwrap();
-        match longest_so_far.entry((point, visited)) {
+fn bfs(start: usize, end: usize, graph: &Graph<usize>) -> usize {
+    /* Visited junctions are encoded into a 64 bit integer, with their index as bit */
+    let mut costs = Vec::new();
+    let mut queue = VecDeque::from([(start, 0, 0)]);
+    let mut longest_so_far: FastMap<(usize, usize), usize> = FastMap::default();
+
+    let mut iterations = 0;
+    while let Some((node, cost, mut visited)) = queue.pop_front() {
+        cancel::check();
+        iterations += 1;
+        progress::report(|| Progress {
+            iterations,
+            states: Some(longest_so_far.len()),
+            best: costs.iter().max().copied(),
+        });
+        match longest_so_far.entry((node, visited)) {
             Entry::Occupied(length) if length.get() >= &cost => continue,
             Entry::Occupied(mut length) => *length.get_mut() = cost,
-            Entry::Vacant(_) => _ = longest_so_far.insert((point, visited), cost),
+            Entry::Vacant(_) => _ = longest_so_far.insert((node, visited), cost),
         }
 
-        if point == end {
+        if node == end {
             costs.push(cost);
             continue;
         }
 
-        /* Add point to the set */
-        visited |= point_as_usize;
+        /* Add node to the set */
+        visited |= 1 << node;
 
-        for (neighbour, cur_cost) in graph.get(&point).unwrap() {
-            let neighbour_as_usize = point_to_usize_table.get(&neighbour).unwrap();
+        for (neighbour, cur_cost) in graph.neighbours(node) {
             /* check if neighbour has been seen */
-            if (visited & neighbour_as_usize) == 0 {
-                queue.push_back((*neighbour, cost + cur_cost, visited));
+            if (visited & (1 << neighbour)) == 0 {
+                queue.push_back((neighbour, cost + cur_cost, visited));
             }
         }
     }
@@ -331,20 +359,124 @@ fn bfs(start: Point, end: Point, graph: HashMap<Point, HashSet<(Point, usize)>>)
     costs.into_iter().max().unwrap_or(0)
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    let map = parse_input(INPUT);
+fn longest_hike(map: &Map, is_directed: bool) -> usize {
     let start = Point { x: 1, y: 0 };
-    let end = map.get_end();
-    let graph = compact_edges(&map, Point { x: 1, y: 0 }, true);
-    bfs(start, end, graph)
+    let graph = to_graph(compact_edges(map, start, is_directed));
+    assert!(
+        graph.node_count() <= 64,
+        "{} junctions don't fit into the visited set",
+        graph.node_count()
+    );
+    match (
+        graph.index(&start.to_string()),
+        graph.index(&map.get_end().to_string()),
+    ) {
+        (Some(start), Some(end)) => bfs(start, end, &graph),
+        _ => 0,
+    }
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    let map = parse_input(INPUT).remove_slopes();
+pub(crate) fn get_solution_1(input: &str) -> usize {
+    longest_hike(&parse_input(input), true)
+}
+
+pub(crate) fn get_solution_2(input: &str) -> usize {
+    longest_hike(&parse_input(input).remove_slopes(), false)
+}
+
+/// The junctions of the map as a graph, with the lengths of the paths between them
+pub(crate) fn export_graph(inp: &str, format: Format) -> String {
     let start = Point { x: 1, y: 0 };
-    let end = map.get_end();
-    let graph = compact_edges(&map, Point { x: 1, y: 0 }, false);
-    bfs(start, end, graph)
+    to_graph(compact_edges(&parse_input(inp), start, true)).write(format)
+}
+
+/// Checks that the map is a rectangle of paths, forest and slopes, starting at the second tile
+/// of the first row
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    let lines: Vec<_> = checker.lines().into_iter().map(|l| l.trim()).collect();
+    checker.grid(&lines, ".#^>v<");
+    if let Some(first) = lines.first() {
+        if first.get(1..2) != Some(".") {
+            checker.error(first.get(1..2).unwrap_or(first), "expected the start `.`");
+        }
+    }
+    checker.finish()
+}
+
+/// The most junctions per side of a generated map, so that with the start and the end they fit
+/// into the 64 bits of visited junctions of `bfs`
+pub(crate) const MAX_SIZE: usize = 7;
+
+/// Generates a map with a lattice of `size` x `size` junctions (at most [`MAX_SIZE`] per side),
+/// connected by straight paths with random lengths. Slopes always lead right or down. Some of the
+/// paths are left out, as long as every junction can still be entered and left.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let n = size.clamp(1, MAX_SIZE);
+    let positions = |rng: &mut Rng, first: usize| -> Vec<usize> {
+        (1..n).fold(vec![first], |mut pos, _| {
+            pos.push(pos[pos.len() - 1] + rng.range(4, 9));
+            pos
+        })
+    };
+    // the first column of junctions lies right below the start
+    let xs = positions(rng, 1);
+    let first = rng.range(4, 9);
+    let ys = positions(rng, first);
+    let width = xs[n - 1] + 2;
+    let height = ys[n - 1] + rng.range(4, 9) + 1;
+
+    // paths between the junctions, as ((row, col), (row, col))
+    let mut paths: Vec<_> = (0..n)
+        .flat_map(|r| (0..n).map(move |c| (r, c)))
+        .flat_map(|(r, c)| [((r, c), (r, c + 1)), ((r, c), (r + 1, c))])
+        .filter(|(_, (r, c))| *r < n && *c < n)
+        .collect();
+    rng.shuffle(&mut paths);
+
+    let mut outgoing = vec![vec![0; n]; n];
+    let mut incoming = vec![vec![0; n]; n];
+    incoming[0][0] += 1;
+    outgoing[n - 1][n - 1] += 1;
+    for ((r0, c0), (r1, c1)) in &paths {
+        outgoing[*r0][*c0] += 1;
+        incoming[*r1][*c1] += 1;
+    }
+    paths.retain(|((r0, c0), (r1, c1))| {
+        let keep = outgoing[*r0][*c0] < 2 || incoming[*r1][*c1] < 2 || !rng.chance(35);
+        if !keep {
+            outgoing[*r0][*c0] -= 1;
+            incoming[*r1][*c1] -= 1;
+        }
+        keep
+    });
+
+    let mut tiles = vec![vec!['#'; width]; height];
+    let mut dig = |from: (usize, usize), to: (usize, usize), slope: char| {
+        let cells: Vec<_> = match from.0 == to.0 {
+            true => (from.1..=to.1).map(|x| (x, from.0)).collect(),
+            false => (from.0..=to.0).map(|y| (from.1, y)).collect(),
+        };
+        for (x, y) in &cells {
+            tiles[*y][*x] = '.';
+        }
+        // slopes right after leaving and right before entering a junction
+        for (x, y) in [cells[1], cells[cells.len() - 2]] {
+            tiles[y][x] = slope;
+        }
+    };
+
+    dig((0, 1), (ys[0], 1), 'v');
+    dig((ys[n - 1], xs[n - 1]), (height - 1, xs[n - 1]), 'v');
+    for ((r0, c0), (r1, c1)) in paths {
+        let slope = if r0 == r1 { '>' } else { 'v' };
+        dig((ys[r0], xs[c0]), (ys[r1], xs[c1]), slope);
+    }
+
+    tiles
+        .into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
 }
 
 #[test]
@@ -361,3 +493,11 @@ fn test_remove_slopes() {
     let map = parse_input(INPUT).remove_slopes();
     println!("{map}");
 }
+
+#[test]
+fn test_generate_largest() {
+    let inp = generate(&mut Rng::seed(42), 100);
+    assert_eq!(inp, generate(&mut Rng::seed(42), MAX_SIZE));
+    assert!(get_solution_1(&inp) > 0);
+    assert!(get_solution_2(&inp) >= get_solution_1(&inp));
+}
@@ -1,14 +1,40 @@
-use std::fmt::Display;
+use std::{collections::HashSet, fmt::Display};
 
-static TEST: &str = include_str!("../data/d24t");
-static INPUT: &str = include_str!("../data/d24");
+use crate::days::Example;
+use crate::params::{self, Param, Params};
+use crate::util::{
+    diag::{Checker, Diagnostic},
+    rational::Rational,
+    rng::Rng,
+};
 
-#[derive(Debug, Clone, Copy)]
+static TEST: &str = include_str!("../data/d24t");
+pub(crate) static INPUT: &str = include_str!("../data/d24");
+pub(crate) static TITLE: &str = "Never Tell Me The Odds";
+pub(crate) static EXAMPLES: &[Example] =
+    &[Example::new(TEST, &[1, 2]).params(&[("area_min", "7"), ("area_max", "27")])];
+
+pub(crate) static PARAMS: &[Param] = &[
+    Param {
+        name: "area_min",
+        default: "200000000000000",
+        validate: params::integer,
+        help: "lower bound of the test area in part 1",
+    },
+    Param {
+        name: "area_max",
+        default: "400000000000000",
+        validate: params::integer,
+        help: "upper bound of the test area in part 1",
+    },
+];
+
+#[derive(Debug, Clone)]
 struct HailStone2D {
-    x: f64,
-    y: f64,
-    dx: f64,
-    dy: f64,
+    x: Rational,
+    y: Rational,
+    dx: Rational,
+    dy: Rational,
 }
 
 impl From<HailStoneVec> for HailStone2D {
@@ -22,11 +48,11 @@ impl From<HailStoneVec> for HailStone2D {
     }
 }
 
-impl std::ops::Mul<f64> for HailStone2D {
-    type Output = (f64, f64);
+impl std::ops::Mul<&Rational> for &HailStone2D {
+    type Output = (Rational, Rational);
 
-    fn mul(self, rhs: f64) -> Self::Output {
-        (self.x + rhs * self.dx, self.y + rhs * self.dy)
+    fn mul(self, rhs: &Rational) -> Self::Output {
+        (&self.x + &(rhs * &self.dx), &self.y + &(rhs * &self.dy))
     }
 }
 
@@ -37,146 +63,136 @@ impl Display for HailStone2D {
 }
 
 impl HailStone2D {
-    fn intersect(&self, other: &Self) -> Option<(f64, f64)> {
-        if self.is_parallel_to(other) {
+    fn intersect(&self, other: &Self) -> Option<(Rational, Rational)> {
+        // solving p1 + t1 * v1 = p2 + t2 * v2 by crossing both sides with v2 and v1 respectively
+        let cross =
+            |x1: &Rational, y1: &Rational, x2: &Rational, y2: &Rational| &(x1 * y2) - &(y1 * x2);
+
+        let denom = cross(&self.dx, &self.dy, &other.dx, &other.dy);
+        if denom.is_zero() {
+            // parallel
             return None;
         }
 
-        let HailStone2D {
-            x: x1,
-            y: y1,
-            dx: dx1,
-            dy: dy1,
-        } = self;
-
-        let HailStone2D {
-            x: x2,
-            y: y2,
-            dx: dx2,
-            dy: dy2,
-        } = other;
-
-        let t1 = (dx2 * (y1 - y2) + dy2 * (x2 - x1)) / ((dx1 * dy2) - (dy1 * dx2));
-        let t2 = (x1 + t1 * dx1 - x2) / dx2;
+        let (px, py) = (&other.x - &self.x, &other.y - &self.y);
+        let t1 = &cross(&px, &py, &other.dx, &other.dy) / &denom;
+        let t2 = &cross(&px, &py, &self.dx, &self.dy) / &denom;
 
         Some((t1, t2))
     }
-
-    fn is_parallel_to(&self, other: &Self) -> bool {
-        let m = (self.dx / other.dx).abs();
-        self.dx == (other.dx * m) && self.dy == (other.dy * m)
-    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct Vec3D {
-    x1: f64,
-    x2: f64,
-    x3: f64,
+    x1: Rational,
+    x2: Rational,
+    x3: Rational,
 }
 
 impl Vec3D {
-    fn new(x1: f64, x2: f64, x3: f64) -> Self {
+    fn new(x1: Rational, x2: Rational, x3: Rational) -> Self {
         Self { x1, x2, x3 }
     }
     fn cross_product(&self, other: &Self) -> Self {
-        let x1 = (self.x2 * other.x3) - (self.x3 * other.x2);
-        let x2 = (self.x3 * other.x1) - (self.x1 * other.x3);
-        let x3 = (self.x1 * other.x2) - (self.x2 * other.x1);
+        let x1 = &(&self.x2 * &other.x3) - &(&self.x3 * &other.x2);
+        let x2 = &(&self.x3 * &other.x1) - &(&self.x1 * &other.x3);
+        let x3 = &(&self.x1 * &other.x2) - &(&self.x2 * &other.x1);
 
         Self { x1, x2, x3 }
     }
 
-    fn dot_product(&self, other: &Self) -> f64 {
-        (self.x1 * other.x1) + (self.x2 * other.x2) + (self.x3 * other.x3)
+    fn dot_product(&self, other: &Self) -> Rational {
+        &(&(&self.x1 * &other.x1) + &(&self.x2 * &other.x2)) + &(&self.x3 * &other.x3)
     }
 }
 
-impl std::ops::Sub for Vec3D {
-    type Output = Self;
+impl std::ops::Sub for &Vec3D {
+    type Output = Vec3D;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x1: self.x1 - rhs.x1,
-            x2: self.x2 - rhs.x2,
-            x3: self.x3 - rhs.x3,
+        Vec3D {
+            x1: &self.x1 - &rhs.x1,
+            x2: &self.x2 - &rhs.x2,
+            x3: &self.x3 - &rhs.x3,
         }
     }
 }
 
-impl std::ops::Add for Vec3D {
-    type Output = Self;
+impl std::ops::Add for &Vec3D {
+    type Output = Vec3D;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x1: self.x1 + rhs.x1,
-            x2: self.x2 + rhs.x2,
-            x3: self.x3 + rhs.x3,
+        Vec3D {
+            x1: &self.x1 + &rhs.x1,
+            x2: &self.x2 + &rhs.x2,
+            x3: &self.x3 + &rhs.x3,
         }
     }
 }
 
-impl std::ops::Mul<f64> for Vec3D {
-    type Output = Self;
+impl std::ops::Mul<&Rational> for &Vec3D {
+    type Output = Vec3D;
 
-    fn mul(self, rhs: f64) -> Self::Output {
-        Self {
-            x1: self.x1 * rhs,
-            x2: self.x2 * rhs,
-            x3: self.x3 * rhs,
+    fn mul(self, rhs: &Rational) -> Self::Output {
+        Vec3D {
+            x1: &self.x1 * rhs,
+            x2: &self.x2 * rhs,
+            x3: &self.x3 * rhs,
         }
     }
 }
 
-impl std::ops::Div<f64> for Vec3D {
-    type Output = Self;
+impl std::ops::Div<&Rational> for &Vec3D {
+    type Output = Vec3D;
 
-    fn div(self, rhs: f64) -> Self::Output {
-        Self {
-            x1: self.x1 / rhs,
-            x2: self.x2 / rhs,
-            x3: self.x3 / rhs,
+    fn div(self, rhs: &Rational) -> Self::Output {
+        Vec3D {
+            x1: &self.x1 / rhs,
+            x2: &self.x2 / rhs,
+            x3: &self.x3 / rhs,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct HailStoneVec {
     p: Vec3D,
     v: Vec3D,
 }
 
-impl From<&[f64]> for HailStoneVec {
-    fn from(value: &[f64]) -> Self {
+impl From<&[i128]> for HailStoneVec {
+    fn from(value: &[i128]) -> Self {
         assert_eq!(value.len(), 6);
+        let value: Vec<Rational> = value.iter().map(|&v| v.into()).collect();
+        let [x, y, z, dx, dy, dz] = <[Rational; 6]>::try_from(value).unwrap();
         Self {
-            p: Vec3D::new(value[0], value[1], value[2]),
-            v: Vec3D::new(value[3], value[4], value[5]),
+            p: Vec3D::new(x, y, z),
+            v: Vec3D::new(dx, dy, dz),
         }
     }
 }
 
-fn solve(s0: HailStoneVec, s1: HailStoneVec, s2: HailStoneVec) -> HailStoneVec {
+fn solve(s0: &HailStoneVec, s1: &HailStoneVec, s2: &HailStoneVec) -> HailStoneVec {
     /* this solution is taken from reddit https://www.reddit.com/r/adventofcode/comments/18pnycy/comment/kxqjg33/?utm_source=share&utm_medium=web3x&utm_name=web3xcss&utm_term=1&utm_content=share_button,
      * i'm not solving 9 linear equations by hand...*/
 
     /* get position and velocity relative to stone 0 */
-    let p1 = s1.p - s0.p;
-    let v1 = s1.v - s0.v;
-    let p2 = s2.p - s0.p;
-    let v2 = s2.v - s0.v;
+    let p1 = &s1.p - &s0.p;
+    let v1 = &s1.v - &s0.v;
+    let p2 = &s2.p - &s0.p;
+    let v2 = &s2.v - &s0.v;
 
     /* the times when the collision happens, relative to stone 0 */
-    let t1 = (-(p1.cross_product(&p2).dot_product(&v2))) / v1.cross_product(&p2).dot_product(&v2);
-    let t2 = (-(p1.cross_product(&p2).dot_product(&v1))) / p1.cross_product(&v2).dot_product(&v1);
+    let t1 = -(p1.cross_product(&p2).dot_product(&v2)) / v1.cross_product(&p2).dot_product(&v2);
+    let t2 = -(p1.cross_product(&p2).dot_product(&v1)) / p1.cross_product(&v2).dot_product(&v1);
 
     /* caluclate the actual collision points */
-    let c1 = s1.p + (s1.v * t1);
-    let c2 = s2.p + (s2.v * t2);
+    let c1 = &s1.p + &(&s1.v * &t1);
+    let c2 = &s2.p + &(&s2.v * &t2);
 
     /* calculate the stone from this */
-    let v = (c2 - c1) / (t2 - t1);
-    let p = c1 - (v * t1);
+    let v = &(&c2 - &c1) / &(&t2 - &t1);
+    let p = &c1 - &(&v * &t1);
 
     HailStoneVec { p, v }
 }
@@ -185,22 +201,23 @@ fn parse_input(inp: &str) -> Vec<HailStoneVec> {
     inp.lines()
         .map(|line| {
             line.split([',', '@'])
-                .filter_map(|val| val.trim().parse::<f64>().ok())
+                .filter_map(|val| val.trim().parse::<i128>().ok())
                 .collect::<Vec<_>>()
         })
         .map(|pos| pos.as_slice().into())
         .collect()
 }
 
-fn determine_intersection(hss: Vec<HailStone2D>, lower: f64, upper: f64) -> usize {
+fn determine_intersection(hss: Vec<HailStone2D>, lower: Rational, upper: Rational) -> usize {
+    let zero = Rational::from(0);
     let mut count = 0;
     for (i, hs1) in hss.iter().enumerate() {
         for hs2 in &hss[i + 1..] {
             if let Some((t1, t2)) = hs1.intersect(hs2) {
-                if t1 < 0. || t2 < 0. {
+                if t1 < zero || t2 < zero {
                     continue;
                 }
-                let (x1, y1) = *hs1 * t1;
+                let (x1, y1) = hs1 * &t1;
                 if (x1 >= lower) && (x1 <= upper) && (y1 >= lower) && (y1 <= upper) {
                     count += 1;
                 }
@@ -211,13 +228,79 @@ fn determine_intersection(hss: Vec<HailStone2D>, lower: f64, upper: f64) -> usiz
     count
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    let hss: Vec<HailStone2D> = parse_input(INPUT).into_iter().map(|hs| hs.into()).collect();
-    determine_intersection(hss, 200000000000000., 400000000000000.)
+pub(crate) fn get_solution_1(input: &str, params: &Params) -> usize {
+    let hss: Vec<HailStone2D> = parse_input(input).into_iter().map(|hs| hs.into()).collect();
+    let bound = |name| Rational::from(params.get::<i128>(name));
+    determine_intersection(hss, bound("area_min"), bound("area_max"))
 }
 
-pub(crate) fn get_solution_2() -> usize {
-    let hss: Vec<HailStoneVec> = parse_input(INPUT).into_iter().map(|hs| hs.into()).collect();
-    let stone = solve(hss[0], hss[1], hss[2]);
-    (stone.p.x1 + stone.p.x2 + stone.p.x3) as usize
+pub(crate) fn get_solution_2(input: &str) -> i128 {
+    let hss = parse_input(input);
+    let stone = solve(&hss[0], &hss[1], &hss[2]);
+    (&(&stone.p.x1 + &stone.p.x2) + &stone.p.x3)
+        .to_integer()
+        .and_then(|n| n.to_i128())
+        .expect("the rock position is an integer")
+}
+
+/// Checks that every line has the form `px, py, pz @ vx, vy, vz`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        let Some((position, velocity)) = checker.split_once(line, "@") else {
+            continue;
+        };
+        for vec in [position, velocity] {
+            let values: Vec<_> = vec.split(',').map(|v| v.trim()).collect();
+            if values.len() != 3 {
+                checker.error(vec.trim(), "expected three values `x, y, z`");
+            }
+            for value in values {
+                checker.parse::<i64>(value, "a number");
+            }
+        }
+    }
+    checker.finish()
+}
+
+/// Generates `size` hailstones (at least three), which are all hit by the same rock at distinct
+/// times
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    let coord = |rng: &mut Rng| rng.range(250_000_000_000_000, 350_000_000_000_000) as i64;
+    let velocity = |rng: &mut Rng| loop {
+        let v = rng.range(0, 401) as i64 - 200;
+        if v != 0 {
+            break v;
+        }
+    };
+    let rock_p = [(); 3].map(|_| coord(rng));
+    let rock_v = [(); 3].map(|_| velocity(rng));
+
+    let mut times = HashSet::new();
+    let mut inp = String::new();
+    while times.len() < size {
+        let t = rng.range(1, 500_000_000_000) as i64;
+        let v = [(); 3].map(|_| velocity(rng));
+        if v == rock_v || !times.insert(t) {
+            continue;
+        }
+        let p: Vec<i64> = (0..3).map(|i| rock_p[i] + (rock_v[i] - v[i]) * t).collect();
+        inp.push_str(&format!(
+            "{}, {}, {} @ {}, {}, {}\n",
+            p[0], p[1], p[2], v[0], v[1], v[2]
+        ));
+    }
+
+    inp
+}
+
+#[test]
+fn test_example() {
+    let mut params = Params::new(PARAMS);
+    params.set("area_min", "7").unwrap();
+    params.set("area_max", "27").unwrap();
+
+    assert_eq!(get_solution_1(TEST, &params), 2);
+    assert_eq!(get_solution_2(TEST), 47);
 }
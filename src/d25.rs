@@ -1,40 +1,20 @@
-use std::{
-    collections::{HashMap, HashSet},
-    time::{SystemTime, UNIX_EPOCH},
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::days::Example;
+use crate::util::{
+    cancel,
+    diag::{Checker, Diagnostic},
+    graph::{self, Format},
+    progress::{self, Progress},
+    rng::Rng,
 };
 
 #[allow(unused)]
 static TEST: &str = include_str!("../data/d25t");
-static INPUT: &str = include_str!("../data/d25");
-
-struct Rng {
-    state: usize,
-}
-
-impl Rng {
-    fn seed(seed: usize) -> Self {
-        Self { state: seed }
-    }
-
-    fn seed_from_epoch() -> Self {
-        let state = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as usize;
-
-        Self { state }
-    }
-
-    fn next(&mut self) -> usize {
-        let mut tmp = self.state;
-        tmp ^= tmp << 13;
-        tmp ^= tmp >> 17;
-        tmp ^= tmp << 5;
-        self.state = tmp;
-
-        tmp
-    }
-}
+pub(crate) static INPUT: &str = include_str!("../data/d25");
+pub(crate) static TITLE: &str = "Snowverload";
+// data/d25t is empty, so there is no example to run
+pub(crate) static EXAMPLES: &[Example] = &[];
 
 struct Graph {
     n_edges: usize,
@@ -134,49 +114,44 @@ fn karger(graph: &Graph, seed_opt: Option<usize>) -> Vec<UnionRank> {
     subsets
 }
 
-fn parse_input(inp: &'static str) -> Graph {
-    /* collect a hashset of all nodes*/
-    let mut vertices = HashSet::new();
-    let mut edges = Vec::new();
-
-    /* collect a vec of all the edges */
+/// The components as a graph, with an edge per wire
+fn parse_components(inp: &str) -> graph::Graph<()> {
+    let mut components = graph::Graph::undirected();
     for line in inp.lines() {
         let parsed_line = line.split(':').collect::<Vec<_>>();
         let parent = parsed_line[0].trim();
         let children = parsed_line[1]
             .split_whitespace()
             .map(|s| s.trim())
-            .collect::<HashSet<&str>>();
+            .collect::<BTreeSet<&str>>();
 
-        vertices.insert(parent);
+        components.node(parent);
         for child in children {
-            vertices.insert(child);
-            edges.push((parent, child));
+            components.add_edge(parent, child, ());
         }
     }
-    /* lookup table for vertices to their indices */
-    let index_table: HashMap<&str, usize> = vertices
-        .iter()
-        .enumerate()
-        .map(|(i, name)| (*name, i))
-        .collect();
+    components
+}
 
-    let edges_as_indices: Vec<_> = edges
-        .into_iter()
-        .map(|(u, v)| (*index_table.get(u).unwrap(), *index_table.get(v).unwrap()))
-        .collect();
+fn parse_input(inp: &str) -> Graph {
+    let components = parse_components(inp);
+    let edges: Vec<_> = components.edges().map(|(u, v, _)| (u, v)).collect();
 
-    let graph = Graph {
-        n_edges: edges_as_indices.len(),
-        n_vertices: index_table.len(),
-        edges: edges_as_indices,
-    };
+    Graph {
+        n_edges: edges.len(),
+        n_vertices: components.node_count(),
+        edges,
+    }
+}
 
-    graph
+pub(crate) fn export_graph(inp: &str, format: Format) -> String {
+    parse_components(inp).write(format)
 }
 
 fn find_minimal_cut(graph: &Graph) -> usize {
-    loop {
+    let mut fewest_cut_edges = None;
+    for attempt in 1.. {
+        cancel::check();
         let mut subsets = karger(&graph, None);
         /* determine the cut edges */
         let edges: Vec<_> = graph
@@ -194,12 +169,99 @@ fn find_minimal_cut(graph: &Graph) -> usize {
         }
 
         if edges.len() == 3 {
-            break sizes.values().product::<usize>();
+            return sizes.values().product::<usize>();
         }
+
+        let cut_edges = edges.len();
+        fewest_cut_edges = Some(fewest_cut_edges.map_or(cut_edges, |n: usize| n.min(cut_edges)));
+        progress::report(|| Progress {
+            iterations: attempt,
+            best: fewest_cut_edges,
+            ..Default::default()
+        });
     }
+    unreachable!()
 }
 
-pub(crate) fn get_solution_1() -> usize {
-    let graph = parse_input(INPUT);
+pub(crate) fn get_solution_1(input: &str) -> usize {
+    let graph = parse_input(input);
     find_minimal_cut(&graph)
 }
+
+/// Checks that every line has the form `<component>: <component> ...`
+pub(crate) fn check(inp: &str) -> Vec<Diagnostic> {
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        let Some((component, connected)) = checker.split_once(line, ":") else {
+            continue;
+        };
+        if connected.trim().is_empty() {
+            checker.error_after(line, "expected connected components");
+        }
+        for name in std::iter::once(component).chain(connected.split_whitespace()) {
+            if name.is_empty() {
+                checker.error(name, "expected a component");
+            }
+            checker.chars(name, "abcdefghijklmnopqrstuvwxyz");
+        }
+    }
+    checker.finish()
+}
+
+/// Generates two groups of `size / 2` components, which are connected by exactly three wires.
+/// Within a group, each component is wired to its four nearest neighbours and sometimes to a
+/// random other one.
+pub(crate) fn generate(rng: &mut Rng, size: usize) -> String {
+    let len = (size / 2).max(6);
+    let mut names = HashSet::new();
+    let mut components = Vec::new();
+    while components.len() < 2 * len {
+        let name = rng.word(3);
+        if names.insert(name.clone()) {
+            components.push(name);
+        }
+    }
+
+    // the first group are the components 0..len, the second one len..2 * len
+    let mut wires = BTreeSet::new();
+    let mut wire = |u: usize, v: usize| {
+        if u != v {
+            wires.insert((u.min(v), u.max(v)));
+        }
+    };
+    for offset in [0, len] {
+        for i in 0..len {
+            wire(offset + i, offset + (i + 1) % len);
+            wire(offset + i, offset + (i + 2) % len);
+            if rng.chance(50) {
+                wire(offset + i, offset + rng.range(0, len));
+            }
+        }
+    }
+    let mut a: Vec<usize> = (0..len).collect();
+    let mut b: Vec<usize> = (len..2 * len).collect();
+    rng.shuffle(&mut a);
+    rng.shuffle(&mut b);
+    for i in 0..3 {
+        wire(a[i], b[i]);
+    }
+
+    // each wire is only listed by one of its components
+    let mut connected = vec![Vec::new(); 2 * len];
+    for (u, v) in wires {
+        match rng.chance(50) {
+            true => connected[u].push(v),
+            false => connected[v].push(u),
+        }
+    }
+
+    connected
+        .into_iter()
+        .enumerate()
+        .filter(|(_, others)| !others.is_empty())
+        .map(|(u, others)| {
+            let others: Vec<_> = others.iter().map(|v| components[*v].as_str()).collect();
+            format!("{}: {}\n", components[u], others.join(" "))
+        })
+        .collect()
+}
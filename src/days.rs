@@ -0,0 +1,488 @@
+//! Registry of all the days, so the days can be looked up by their number
+//!
+
+use std::{
+    fmt::Display,
+    io::{self, BufRead},
+    str::FromStr,
+};
+
+use crate::params::{Param, Params};
+use crate::util::{
+    bigint::{BigInt, BigUint, ParseBigIntError},
+    diag::Diagnostic,
+    explain::Explanation,
+    graph::Format,
+    rng::Rng,
+};
+use crate::{
+    d01, d02, d03, d04, d05, d06, d07, d08, d09, d10, d11, d12, d13, d14, d15, d16, d17, d18, d19,
+    d20, d21, d22, d23, d24, d25,
+};
+
+/// The answer of a part
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Answer {
+    Int(i128),
+    /// only for answers which don't fit into an `Int`
+    Big(BigInt),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(n) => n.fmt(f),
+            Answer::Big(n) => n.fmt(f),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ( $($t:ty),* ) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(u32, u64, usize, isize, i128);
+
+impl FromStr for Answer {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(n) => Ok(Answer::Int(n)),
+            Err(_) => s.parse().map(Answer::Big),
+        }
+    }
+}
+
+impl From<BigUint> for Answer {
+    fn from(n: BigUint) -> Self {
+        match n.to_u128().and_then(|n| i128::try_from(n).ok()) {
+            Some(n) => Answer::Int(n),
+            None => Answer::Big(n.into()),
+        }
+    }
+}
+
+/// Solves a part for the given input, or tells why the parameters don't fit it
+pub(crate) type Part = fn(&str, &Params) -> Result<Answer, String>;
+
+/// Solves a part reading the input line by line, without keeping it in memory
+pub(crate) type Stream = fn(&mut dyn BufRead, &Params) -> io::Result<Answer>;
+
+/// Makes an input bigger by repeating it `factor` times, in both directions for grids
+pub(crate) type Scale = fn(&str, usize) -> Result<String, String>;
+
+/// Explains how the answer of a part is derived from an input with the given parameters
+pub(crate) type Explain = fn(&str, &Params, usize) -> Explanation;
+
+/// An example input of the puzzle description
+pub(crate) struct Example {
+    pub(crate) input: &'static str,
+    /// the parts the example is given for
+    pub(crate) parts: &'static [usize],
+    /// the parameters the example is given for, as (name, value)
+    pub(crate) params: &'static [(&'static str, &'static str)],
+}
+
+impl Example {
+    pub(crate) const fn new(input: &'static str, parts: &'static [usize]) -> Self {
+        Self {
+            input,
+            parts,
+            params: &[],
+        }
+    }
+
+    pub(crate) const fn params(self, params: &'static [(&'static str, &'static str)]) -> Self {
+        Self { params, ..self }
+    }
+}
+
+pub(crate) struct Day {
+    pub(crate) day: u8,
+    /// the title of the puzzle
+    pub(crate) title: &'static str,
+    /// the puzzle input
+    pub(crate) input: &'static str,
+    pub(crate) parts: &'static [Part],
+    /// the tunable constants of the puzzle
+    pub(crate) params: &'static [Param],
+    /// the examples of the puzzle description
+    pub(crate) examples: &'static [Example],
+    /// checks the format of an input without solving it
+    pub(crate) check: Option<fn(&str) -> Vec<Diagnostic>>,
+    /// generates a random input with a size of roughly n
+    pub(crate) generate: Option<fn(&mut Rng, usize) -> String>,
+    /// the largest size the generator supports, it clamps bigger sizes to it
+    pub(crate) max_size: Option<usize>,
+    /// makes an input bigger to see how the solver scales
+    pub(crate) scale: Option<Scale>,
+    /// writes the graph of an input, for the days which search a graph
+    pub(crate) graph: Option<fn(&str, Format) -> String>,
+    /// pretty-prints the parsed input, for the show command of the repl
+    pub(crate) show: Option<fn(&str) -> String>,
+    /// explains the answers, for the days whose answers are hard to check by hand
+    pub(crate) explain: Option<Explain>,
+    /// the parts solved from a reader, for the days whose inputs are a line per item
+    pub(crate) stream: Option<&'static [Stream]>,
+}
+
+/// Turns a solution into a [`Part`], with `params` if the solution takes them
+macro_rules! part {
+    ( $f:path ) => {
+        |input, _| Ok($f(input).into())
+    };
+    ( $f:path, params ) => {
+        |input, params| Ok($f(input, params).into())
+    };
+    ( $f:path, params, fallible ) => {
+        |input, params| $f(input, params).map(Answer::from)
+    };
+}
+
+/// Turns a streaming solution into a [`Stream`], like [`part!`]
+macro_rules! stream {
+    ( $f:path ) => {
+        |reader, _| $f(reader).map(Answer::from)
+    };
+    ( $f:path, params ) => {
+        |reader, params| $f(reader, params).map(Answer::from)
+    };
+}
+
+/// Turns an explanation into an [`Explain`], like [`part!`]
+macro_rules! explain {
+    ( $f:path ) => {
+        |input, _, part| $f(input, part)
+    };
+    ( $f:path, params ) => {
+        |input, params, part| $f(input, params, part)
+    };
+}
+
+macro_rules! day {
+    ( $d:literal, $m:ident ) => {
+        day!($d, $m, [part!($m::get_solution_1), part!($m::get_solution_2)])
+    };
+    ( $d:literal, $m:ident, [$($part:expr),+] ) => {
+        day!($d, $m, [$($part),+], &[])
+    };
+    ( $d:literal, $m:ident, [$($part:expr),+], params ) => {
+        day!($d, $m, [$($part),+], $m::PARAMS)
+    };
+    ( $d:literal, $m:ident, [$($part:expr),+], $params:expr ) => {
+        Day {
+            day: $d,
+            title: $m::TITLE,
+            input: $m::INPUT,
+            parts: &[$($part),+],
+            params: $params,
+            examples: $m::EXAMPLES,
+            check: Some($m::check),
+            generate: Some($m::generate),
+            max_size: None,
+            scale: None,
+            graph: None,
+            show: None,
+            explain: None,
+            stream: None,
+        }
+    };
+}
+
+pub(crate) static DAYS: [Day; 25] = [
+    Day {
+        stream: Some(&[
+            stream!(d01::stream_solution_1),
+            stream!(d01::stream_solution_2),
+        ]),
+        ..day!(1, d01)
+    },
+    Day {
+        scale: Some(d02::scale),
+        stream: Some(&[
+            stream!(d02::stream_solution_1, params),
+            stream!(d02::stream_solution_2),
+        ]),
+        ..day!(
+            2,
+            d02,
+            [
+                part!(d02::get_solution_1, params),
+                part!(d02::get_solution_2)
+            ],
+            params
+        )
+    },
+    day!(3, d03),
+    Day {
+        scale: Some(d04::scale),
+        explain: Some(explain!(d04::explain)),
+        stream: Some(&[
+            stream!(d04::stream_solution_1),
+            stream!(d04::stream_solution_2),
+        ]),
+        ..day!(4, d04)
+    },
+    Day {
+        scale: Some(d05::scale),
+        show: Some(d05::show),
+        ..day!(5, d05)
+    },
+    // the input of day 6 is small enough to be typed in directly
+    Day {
+        day: 6,
+        title: d06::TITLE,
+        input: "",
+        parts: &[
+            |_, _| Ok(d06::get_solution_1().into()),
+            |_, _| Ok(d06::get_solution_2().into()),
+        ],
+        params: &[],
+        // the example of day 6 isn't an input either
+        examples: &[],
+        check: None,
+        generate: None,
+        max_size: None,
+        scale: None,
+        graph: None,
+        show: None,
+        explain: None,
+        stream: None,
+    },
+    Day {
+        scale: Some(d07::scale),
+        explain: Some(explain!(d07::explain)),
+        stream: Some(&[
+            stream!(d07::stream_solution_1),
+            stream!(d07::stream_solution_2),
+        ]),
+        ..day!(7, d07)
+    },
+    Day {
+        graph: Some(d08::export_graph),
+        ..day!(8, d08)
+    },
+    Day {
+        stream: Some(&[
+            stream!(d09::stream_solution_1),
+            stream!(d09::stream_solution_2),
+        ]),
+        ..day!(9, d09)
+    },
+    Day {
+        scale: Some(d10::scale),
+        ..day!(10, d10)
+    },
+    day!(
+        11,
+        d11,
+        [
+            part!(d11::get_solution_1),
+            part!(d11::get_solution_2, params)
+        ],
+        params
+    ),
+    Day {
+        show: Some(d12::show),
+        stream: Some(&[
+            stream!(d12::stream_solution_1),
+            stream!(d12::stream_solution_2, params),
+        ]),
+        ..day!(
+            12,
+            d12,
+            [
+                part!(d12::get_solution_1),
+                part!(d12::get_solution_2, params)
+            ],
+            params
+        )
+    },
+    Day {
+        explain: Some(explain!(d13::explain)),
+        ..day!(13, d13)
+    },
+    Day {
+        scale: Some(d14::scale),
+        ..day!(
+            14,
+            d14,
+            [
+                part!(d14::get_solution_1),
+                part!(d14::get_solution_2, params)
+            ],
+            params
+        )
+    },
+    Day {
+        stream: Some(&[
+            stream!(d15::stream_solution_1),
+            stream!(d15::stream_solution_2),
+        ]),
+        ..day!(15, d15)
+    },
+    Day {
+        scale: Some(d16::scale),
+        ..day!(16, d16)
+    },
+    Day {
+        scale: Some(d17::scale),
+        ..day!(
+            17,
+            d17,
+            [
+                part!(d17::get_solution_1, params),
+                part!(d17::get_solution_2, params)
+            ],
+            params
+        )
+    },
+    Day {
+        stream: Some(&[
+            stream!(d18::stream_solution_1),
+            stream!(d18::stream_solution_2),
+        ]),
+        ..day!(18, d18)
+    },
+    Day {
+        show: Some(d19::show),
+        explain: Some(explain!(d19::explain)),
+        ..day!(19, d19)
+    },
+    Day {
+        graph: Some(d20::export_graph),
+        ..day!(
+            20,
+            d20,
+            [
+                part!(d20::get_solution_1, params),
+                part!(d20::get_solution_2)
+            ],
+            params
+        )
+    },
+    Day {
+        scale: Some(d21::scale),
+        ..day!(
+            21,
+            d21,
+            [
+                part!(d21::get_solution_1, params),
+                part!(d21::get_solution_2, params, fallible)
+            ],
+            params
+        )
+    },
+    Day {
+        show: Some(d22::show),
+        ..day!(22, d22)
+    },
+    Day {
+        max_size: Some(d23::MAX_SIZE),
+        graph: Some(d23::export_graph),
+        ..day!(23, d23)
+    },
+    day!(
+        24,
+        d24,
+        [
+            part!(d24::get_solution_1, params),
+            part!(d24::get_solution_2)
+        ],
+        params
+    ),
+    Day {
+        graph: Some(d25::export_graph),
+        ..day!(25, d25, [part!(d25::get_solution_1)])
+    },
+];
+
+pub(crate) fn get(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[test]
+fn test_generated_inputs_pass_check() {
+    for day in DAYS.iter().filter(|d| d.generate.is_some()) {
+        let inp = day.generate.unwrap()(&mut Rng::seed(42), 5);
+        assert_eq!(day.check.unwrap()(&inp), Vec::new(), "day {}", day.day);
+    }
+}
+
+#[test]
+fn test_generate_is_deterministic() {
+    for day in DAYS.iter().filter(|d| d.generate.is_some()) {
+        let generate = day.generate.unwrap();
+        let first = generate(&mut Rng::seed(42), 20);
+        let second = generate(&mut Rng::seed(42), 20);
+        assert_eq!(first, second, "day {}", day.day);
+        assert!(first.ends_with('\n'), "day {}", day.day);
+    }
+}
+
+#[test]
+fn test_scaled_inputs_pass_check() {
+    for day in DAYS.iter().filter(|d| d.scale.is_some()) {
+        let inp = day.generate.unwrap()(&mut Rng::seed(42), 5);
+        let scaled = day.scale.unwrap()(&inp, 3).unwrap();
+        assert!(scaled.len() > inp.len(), "day {}", day.day);
+        assert_eq!(day.check.unwrap()(&scaled), Vec::new(), "day {}", day.day);
+    }
+}
+
+#[test]
+fn test_examples_fit_their_day() {
+    for day in &DAYS {
+        for example in day.examples {
+            assert!(!example.input.trim().is_empty(), "day {}", day.day);
+            let mut params = Params::new(day.params);
+            for (name, value) in example.params {
+                assert!(params.set(name, value).is_ok(), "day {}", day.day);
+            }
+            assert!(
+                example
+                    .parts
+                    .iter()
+                    .all(|p| (1..=day.parts.len()).contains(p)),
+                "day {}",
+                day.day
+            );
+        }
+    }
+}
+
+#[test]
+fn test_streams_match_parts() {
+    for day in DAYS.iter().filter(|d| d.stream.is_some()) {
+        let params = Params::new(day.params);
+        let inp = day.generate.unwrap()(&mut Rng::seed(42), 20);
+        for (part, stream) in day.parts.iter().zip(day.stream.unwrap()) {
+            let streamed = stream(&mut inp.as_bytes(), &params).unwrap();
+            assert_eq!(Ok(streamed), part(&inp, &params), "day {}", day.day);
+        }
+    }
+}
+
+#[test]
+fn test_big_answer() {
+    let small = BigUint::from(u64::MAX);
+    assert_eq!(Answer::from(small.clone()), Answer::Int(u64::MAX as i128));
+
+    let big = [small.clone(), small.clone(), small]
+        .into_iter()
+        .product::<BigUint>();
+    assert!(matches!(Answer::from(big.clone()), Answer::Big(_)));
+    assert_eq!(
+        format!("{:>70}", Answer::from(big.clone())),
+        format!("{big:>70}")
+    );
+}
@@ -1,5 +1,21 @@
-use std::time::Instant;
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
 
+use bench::History;
+use cache::Cache;
+use cli::Command;
+use days::{Day, Example, Explain};
+use params::Params;
+use util::{alloc, cancel, checked, diag, explain, frames, graph::Format, log, progress, rng::Rng};
+
+mod bench;
+mod cache;
+mod cli;
 mod d01;
 mod d02;
 mod d03;
@@ -25,94 +41,575 @@ mod d22;
 mod d23;
 mod d24;
 mod d25;
+mod days;
+mod params;
+mod repl;
+mod report;
+mod serve;
 mod util;
+mod watch;
+
+#[global_allocator]
+static ALLOC: alloc::Counting = alloc::Counting;
+
+fn main() -> ExitCode {
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
 
-fn main() {
-    aoc_result!(1, 1, d01::get_solution_1());
-    aoc_result!(1, 2, d01::get_solution_2());
+    let spec = std::env::var("AOC_LOG").ok();
+    if let Err(e) = log::init(options.verbosity, spec.as_deref()) {
+        eprintln!("invalid AOC_LOG: {e}");
+        return ExitCode::FAILURE;
+    }
 
-    aoc_result!(2, 1, d02::get_solution_1());
-    aoc_result!(2, 2, d02::get_solution_2());
+    if options.help {
+        println!("{}", cli::USAGE);
+        if let Command::Run { day, .. } | Command::Watch { day, .. } | Command::Stream { day, .. } =
+            options.command
+        {
+            match days::get(day).map(|d| d.params) {
+                Some([]) | None => (),
+                Some(declared) => {
+                    println!("\nparameters of day {day}:\n{}", params::describe(declared))
+                }
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
 
-    aoc_result!(3, 1, d03::get_solution_1());
-    aoc_result!(3, 2, d03::get_solution_2());
+    if let Command::Gen { day, seed, size } = options.command {
+        return generate(day, seed, size);
+    }
+    if let Command::Check { day, input } = &options.command {
+        return check(*day, input);
+    }
 
-    aoc_result!(4, 1, d04::get_solution_1());
-    aoc_result!(4, 2, d04::get_solution_2());
+    if let Command::Scale { day, input, factor } = &options.command {
+        return scale(*day, input.as_deref(), *factor);
+    }
+    if let Command::Graph { day, input, format } = &options.command {
+        return graph(*day, input.as_deref(), *format);
+    }
 
-    aoc_result!(5, 1, d05::get_solution_1());
-    aoc_result!(5, 2, d05::get_solution_2());
+    // the history and the cache belong to the directory the days are run in, not to the checkout
+    // the binary was built in
+    let history = History::new(".bench/history");
+    match options.command {
+        Command::Bench { day, runs } => return bench(&history, day, runs),
+        Command::Compare { a, b, threshold } => return compare(&history, a, b, threshold),
+        Command::Report { markdown, redact } => {
+            let rows = report::collect(days::DAYS.iter(), options.timeout);
+            match markdown {
+                true => println!("{}", report::markdown(&rows, redact)),
+                false => println!("{}", report::plain(&rows, redact)),
+            }
+            return ExitCode::SUCCESS;
+        }
+        _ => (),
+    }
 
-    aoc_result!(6, 1, d06::get_solution_1());
-    aoc_result!(6, 2, d06::get_solution_2());
+    let cache = Cache::new(".cache");
+    if let Command::ClearCache = options.command {
+        return match cache.clear() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("failed to remove {}: {e}", cache.dir().display());
+                ExitCode::FAILURE
+            }
+        };
+    }
+    // checked mode, the frames, the logs, the memory usage and the explanations are only of use
+    // when the parts are actually solved
+    let solve = options.no_cache
+        || options.checked
+        || options.memory
+        || options.explain.is_some()
+        || options.frames.is_some()
+        || options.verbosity > 0
+        || spec.is_some();
+    let cache = (!solve).then_some(cache);
 
-    aoc_result!(7, 1, d07::get_solution_1());
-    aoc_result!(7, 2, d07::get_solution_2());
+    if let Some(capture) = options.frames {
+        if let Err(e) = frames::init(capture) {
+            eprintln!("failed to set up frame capture: {e}");
+            return ExitCode::FAILURE;
+        }
+    }
 
-    aoc_result!(8, 1, d08::get_solution_1());
-    aoc_result!(8, 2, d08::get_solution_2());
+    if options.checked {
+        checked::enable();
+    }
+    if options.memory {
+        alloc::enable();
+    }
+    progress::init();
 
-    aoc_result!(9, 1, d09::get_solution_1());
-    aoc_result!(9, 2, d09::get_solution_2());
+    let mut finished = true;
+    match &options.command {
+        Command::Run { day, params } => {
+            let Some(day) = days::get(*day) else {
+                eprintln!("there is no day {day}");
+                return ExitCode::FAILURE;
+            };
+            let example = match options.example {
+                Some(n) => match day.examples.get(n - 1) {
+                    Some(example) => Some(example),
+                    None => {
+                        eprintln!("day {} has no example {n}", day.day);
+                        return ExitCode::FAILURE;
+                    }
+                },
+                None => None,
+            };
+            let explain = match (options.explain, day.explain) {
+                (Some(format), Some(explain)) => Some((explain, format)),
+                (Some(_), None) => {
+                    eprintln!("day {} can't explain its answers", day.day);
+                    return ExitCode::FAILURE;
+                }
+                (None, _) => None,
+            };
+            match overrides(day, example, params) {
+                Ok(values) => {
+                    finished = run(
+                        day,
+                        example,
+                        &values,
+                        cache.as_ref(),
+                        options.timeout,
+                        explain,
+                    )
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::Watch {
+            day,
+            input,
+            parts,
+            params,
+        } => {
+            let Some(day) = days::get(*day) else {
+                eprintln!("there is no day {day}");
+                return ExitCode::FAILURE;
+            };
+            let values = match overrides(day, None, params) {
+                Ok(values) => values,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            if let Some(part) = parts.iter().find(|&&p| p == 0 || p > day.parts.len()) {
+                eprintln!("day {} has no part {part}", day.day);
+                return ExitCode::FAILURE;
+            }
+            let parts = match parts.is_empty() {
+                true => (1..=day.parts.len()).collect(),
+                false => parts.clone(),
+            };
+            let path = input.clone().unwrap_or_else(|| {
+                PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("data/d{:02}", day.day))
+            });
+            watch::watch(day, &path, &parts, &values, options.timeout);
+        }
+        Command::Stream {
+            day,
+            input,
+            parts,
+            params,
+        } => return stream(*day, input.as_deref(), parts, params),
+        Command::Serve { port } => {
+            if let Err(e) = serve::serve(*port, options.timeout) {
+                eprintln!("failed to serve on port {port}: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Repl => {
+            if let Err(e) = repl::repl(std::io::stdin().lock(), std::io::stdout(), options.timeout)
+            {
+                eprintln!("repl failed: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+        _ => {
+            for day in &days::DAYS {
+                let example = match options.example {
+                    Some(n) => match day.examples.get(n - 1) {
+                        Some(example) => Some(example),
+                        None => {
+                            println!("d{:2}: no example {n}", day.day);
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+                match overrides(day, example, &[]) {
+                    Ok(params) => {
+                        finished &=
+                            run(day, example, &params, cache.as_ref(), options.timeout, None)
+                    }
+                    Err(e) => {
+                        eprintln!("{e}");
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+        }
+    }
 
-    aoc_result!(10, 1, d10::get_solution_1());
-    aoc_result!(10, 2, d10::get_solution_2());
+    frames::finish();
 
-    aoc_result!(11, 1, d11::get_solution_1());
-    aoc_result!(11, 2, d11::get_solution_2());
+    match checked::overflowed() || !finished {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+fn generate(day: u8, seed: Option<usize>, size: usize) -> ExitCode {
+    let generate = match days::get(day).map(|d| d.generate) {
+        Some(Some(generate)) => generate,
+        Some(None) => {
+            eprintln!("there is no generator for day {day}");
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("there is no day {day}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let seed = seed.unwrap_or_else(|| Rng::seed_from_epoch().next());
+    eprintln!("seed: {seed}");
+    if let Some(max) = days::get(day)
+        .and_then(|d| d.max_size)
+        .filter(|&max| size > max)
+    {
+        eprintln!("warning: day {day} generates at most size {max}, not {size}");
+    }
 
-    aoc_result!(12, 1, d12::get_solution_1());
-    aoc_result!(12, 2, d12::get_solution_2());
+    print!("{}", generate(&mut Rng::seed(seed), size));
+    ExitCode::SUCCESS
+}
 
-    aoc_result!(13, 1, d13::get_solution_1());
-    aoc_result!(13, 2, d13::get_solution_2());
+fn check(day: u8, path: &Path) -> ExitCode {
+    let check = match days::get(day).map(|d| d.check) {
+        Some(Some(check)) => check,
+        Some(None) => {
+            eprintln!("there is no input to check for day {day}");
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("there is no day {day}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let inp = match fs::read_to_string(path) {
+        Ok(inp) => inp,
+        Err(e) => {
+            eprintln!("failed to read {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
 
-    aoc_result!(14, 1, d14::get_solution_1());
-    aoc_result!(14, 2, d14::get_solution_2());
+    let diags = check(&inp);
+    print!(
+        "{}",
+        diag::render(&inp, &path.display().to_string(), &diags)
+    );
+    match diags.len() {
+        0 => {
+            println!("{}: ok", path.display());
+            ExitCode::SUCCESS
+        }
+        n => {
+            println!("{}: {n} problem(s) found", path.display());
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    aoc_result!(15, 1, d15::get_solution_1());
-    aoc_result!(15, 2, d15::get_solution_2());
+fn scale(day: u8, path: Option<&Path>, factor: usize) -> ExitCode {
+    let (input, scale) = match days::get(day) {
+        Some(Day {
+            input,
+            scale: Some(scale),
+            ..
+        }) => (input, scale),
+        Some(_) => {
+            eprintln!("there is no way to scale the input of day {day}");
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("there is no day {day}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let inp = match path.map(fs::read_to_string) {
+        Some(Ok(inp)) => inp,
+        Some(Err(e)) => {
+            eprintln!("failed to read {}: {e}", path.unwrap().display());
+            return ExitCode::FAILURE;
+        }
+        None => input.to_string(),
+    };
 
-    aoc_result!(16, 1, d16::get_solution_1());
-    aoc_result!(16, 2, d16::get_solution_2());
+    match scale(&inp, factor) {
+        Ok(scaled) => {
+            print!("{scaled}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("failed to scale the input: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    aoc_result!(17, 1, d17::get_solution_1());
-    aoc_result!(17, 2, d17::get_solution_2());
+/// Solves the parts of `day` reading the input in `path`, or stdin, line by line
+fn stream(day: u8, path: Option<&Path>, parts: &[usize], params: &[(String, String)]) -> ExitCode {
+    let (day, streams) = match days::get(day) {
+        Some(
+            d @ Day {
+                stream: Some(streams),
+                ..
+            },
+        ) => (d, *streams),
+        Some(_) => {
+            eprintln!("day {day} can't stream its input");
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("there is no day {day}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let params = match overrides(day, None, params) {
+        Ok(params) => params,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(part) = parts.iter().find(|&&p| p == 0 || p > streams.len()) {
+        eprintln!("day {} has no part {part}", day.day);
+        return ExitCode::FAILURE;
+    }
+    let parts = match parts.is_empty() {
+        true => (1..=streams.len()).collect(),
+        false => parts.to_vec(),
+    };
+    // every part reads the input from the start, but stdin can't be rewound
+    if path.is_none() && parts.len() > 1 {
+        eprintln!("stdin can only be read once, choose a part with --part");
+        return ExitCode::FAILURE;
+    }
 
-    aoc_result!(18, 1, d18::get_solution_1());
-    aoc_result!(18, 2, d18::get_solution_2());
+    let source = path.map_or("stdin".to_string(), |p| p.display().to_string());
+    for part in parts {
+        let mut reader: Box<dyn BufRead> = match path.map(File::open) {
+            Some(Ok(file)) => Box::new(BufReader::new(file)),
+            Some(Err(e)) => {
+                eprintln!("failed to read {source}: {e}");
+                return ExitCode::FAILURE;
+            }
+            None => Box::new(io::stdin().lock()),
+        };
 
-    aoc_result!(19, 1, d19::get_solution_1());
-    aoc_result!(19, 2, d19::get_solution_2());
+        alloc::reset();
+        let now = Instant::now();
+        match streams[part - 1](&mut reader, &params) {
+            Ok(answer) => {
+                let memory = match alloc::enabled() {
+                    true => format!("\t{}", alloc::stats()),
+                    false => String::new(),
+                };
+                println!(
+                    "d{:2}.{part}: {answer:16}\t{:10}us{memory}",
+                    day.day,
+                    now.elapsed().as_micros()
+                );
+            }
+            Err(e) => {
+                eprintln!("failed to read {source}: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
 
-    aoc_result!(20, 1, d20::get_solution_1());
-    aoc_result!(20, 2, d20::get_solution_2());
+fn graph(day: u8, path: Option<&Path>, format: Format) -> ExitCode {
+    let (input, export) = match days::get(day) {
+        Some(Day {
+            input,
+            graph: Some(export),
+            ..
+        }) => (input, export),
+        Some(_) => {
+            eprintln!("day {day} has no graph");
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("there is no day {day}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let inp = match path.map(fs::read_to_string) {
+        Some(Ok(inp)) => inp,
+        Some(Err(e)) => {
+            eprintln!("failed to read {}: {e}", path.unwrap().display());
+            return ExitCode::FAILURE;
+        }
+        None => input.to_string(),
+    };
 
-    aoc_result!(21, 1, d21::get_solution_1());
-    aoc_result!(21, 2, d21::get_solution_2());
+    print!("{}", export(&inp, format));
+    ExitCode::SUCCESS
+}
 
-    aoc_result!(22, 1, d22::get_solution_1());
-    aoc_result!(22, 2, d22::get_solution_2());
+fn bench(history: &History, day: Option<u8>, runs: usize) -> ExitCode {
+    let days: Vec<_> = match day {
+        Some(d) => match days::get(d) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!("there is no day {d}");
+                return ExitCode::FAILURE;
+            }
+        },
+        None => days::DAYS.iter().collect(),
+    };
 
-    aoc_result!(23, 1, d23::get_solution_1());
-    aoc_result!(23, 2, d23::get_solution_2());
+    match bench::bench(history, days.into_iter(), runs) {
+        Ok(run) => {
+            println!("saved as run {} in {}", run.id, history.path().display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("failed to update {}: {e}", history.path().display());
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    aoc_result!(24, 1, d24::get_solution_1());
-    aoc_result!(24, 2, d24::get_solution_2());
+fn compare(history: &History, a: usize, b: usize, threshold: f64) -> ExitCode {
+    let runs = match history.load() {
+        Ok(runs) => runs,
+        Err(e) => {
+            eprintln!("failed to read {}: {e}", history.path().display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let find = |id| runs.iter().find(|r| r.id == id).ok_or(id);
+    match find(a).and_then(|a| Ok((a, find(b)?))) {
+        Ok((a, b)) => {
+            println!("{}", bench::compare(a, b, threshold));
+            ExitCode::SUCCESS
+        }
+        Err(id) => {
+            eprintln!("there is no run {id} in {}", history.path().display());
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    aoc_result!(25, 1, d25::get_solution_1());
+/// The parameters of `day` with the values `example` is given for and then the values given on
+/// the command line
+fn overrides(
+    day: &Day,
+    example: Option<&Example>,
+    params: &[(String, String)],
+) -> Result<Params, String> {
+    let mut values = Params::new(day.params);
+    for (name, value) in example.map_or(&[][..], |e| e.params) {
+        values.set(name, value)?;
+    }
+    for (name, value) in params {
+        values.set(name, value)?;
+    }
+    Ok(values)
 }
 
-#[macro_export]
-macro_rules! aoc_result {
-    ( $d:literal, $p:literal, $r:expr ) => {
+/// Runs the parts of `day` on its input, or only the parts `example` is given for on it, and
+/// prints the explanation of every answer if there is an `explain`, returns false if any of the
+/// parts timed out or failed
+fn run(
+    day: &Day,
+    example: Option<&Example>,
+    params: &Params,
+    cache: Option<&Cache>,
+    timeout: Option<Duration>,
+    explain: Option<(Explain, explain::Format)>,
+) -> bool {
+    let input = example.map_or(day.input, |e| e.input);
+    let mut finished = true;
+    for (i, &part) in day.parts.iter().enumerate() {
+        if example.is_some_and(|e| !e.parts.contains(&(i + 1))) {
+            continue;
+        }
+        if let Some(answer) = cache.and_then(|c| c.get(day.day, i + 1, input, params)) {
+            println!("d{:2}.{}: {:16}\t{:>12}", day.day, i + 1, answer, "cached");
+            continue;
+        }
+
+        progress::start(format!("d{:2}.{}", day.day, i + 1));
+        alloc::reset();
         let now = Instant::now();
-        println!(
-            "d{:2}.{}: {:16}\t{:10}us",
-            $d,
-            $p,
-            $r,
-            now.elapsed().as_micros()
-        );
-    };
+        let result = match timeout {
+            Some(timeout) => {
+                let params = params.clone();
+                cancel::with_timeout(timeout, move || checked::catch(|| part(input, &params)))
+            }
+            None => Some(checked::catch(|| part(input, params))),
+        };
+        progress::finish();
+        match result {
+            Some(Ok(Ok(answer))) => {
+                let elapsed = now.elapsed();
+                let memory = match alloc::enabled() {
+                    true => format!("\t{}", alloc::stats()),
+                    false => String::new(),
+                };
+                println!(
+                    "d{:2}.{}: {:16}\t{:10}us{memory}",
+                    day.day,
+                    i + 1,
+                    answer,
+                    elapsed.as_micros()
+                );
+                if let Some((explain, format)) = explain {
+                    println!("{}", explain(input, params, i + 1).write(format));
+                }
+                if let Some(Err(e)) = cache.map(|c| c.put(day.day, i + 1, input, params, &answer)) {
+                    eprintln!(
+                        "failed to cache the answer of d{:2}.{}: {e}",
+                        day.day,
+                        i + 1
+                    );
+                }
+            }
+            Some(Ok(Err(e))) => {
+                println!("d{:2}.{}: {e}", day.day, i + 1);
+                finished = false;
+            }
+            Some(Err(overflow)) => println!("d{:2}.{}: {overflow}", day.day, i + 1),
+            None => {
+                println!(
+                    "d{:2}.{}: timed out after {:?}",
+                    day.day,
+                    i + 1,
+                    timeout.unwrap()
+                );
+                finished = false;
+            }
+        }
+    }
+    finished
 }
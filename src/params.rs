@@ -0,0 +1,141 @@
+//! Tunable parameters of the days
+//!
+//! Days declare the constants of the puzzle as named [`Param`]s with a default, which can be
+//! overridden on the command line with `--param name=value`.
+
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// A named constant of a day
+#[derive(Debug)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub help: &'static str,
+    /// checks a value before it's set, like [`count`], so `get` can't fail later
+    pub validate: fn(&str) -> Result<(), String>,
+}
+
+/// Accepts any count, 0 included
+pub fn count(value: &str) -> Result<(), String> {
+    value
+        .parse::<usize>()
+        .map(|_| ())
+        .map_err(|e| format!("expected a count ({e})"))
+}
+
+/// Accepts counts of at least 1
+pub fn positive(value: &str) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("expected at least 1".to_string()),
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("expected a count of at least 1 ({e})")),
+    }
+}
+
+/// Accepts any integer, negative ones included
+pub fn integer(value: &str) -> Result<(), String> {
+    value
+        .parse::<i128>()
+        .map(|_| ())
+        .map_err(|e| format!("expected an integer ({e})"))
+}
+
+/// The values of the parameters of a day, the defaults unless overridden
+#[derive(Debug, Default, Clone)]
+pub struct Params {
+    declared: &'static [Param],
+    values: HashMap<&'static str, String>,
+}
+
+impl Params {
+    pub fn new(declared: &'static [Param]) -> Self {
+        Self {
+            declared,
+            values: HashMap::new(),
+        }
+    }
+
+    /// Overrides the value of `name`, fails if the day doesn't declare it or the value is invalid
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match self.declared.iter().find(|p| p.name == name) {
+            Some(param) => {
+                (param.validate)(value)
+                    .map_err(|e| format!("invalid value for parameter `{name}`: {value}, {e}"))?;
+                self.values.insert(param.name, value.to_string());
+                Ok(())
+            }
+            None if self.declared.is_empty() => Err(format!(
+                "unknown parameter `{name}`, this day has no parameters"
+            )),
+            None => Err(format!(
+                "unknown parameter `{name}`, expected one of: {}",
+                self.declared
+                    .iter()
+                    .map(|p| p.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// The value of `name`, panics if it isn't declared or the value can't be parsed, which
+    /// `validate` rules out for values set with `set`
+    pub fn get<T: FromStr>(&self, name: &str) -> T
+    where
+        T::Err: Display,
+    {
+        let param = self
+            .declared
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("parameter `{name}` isn't declared"));
+        let value = self.values.get(name).map_or(param.default, |v| v.as_str());
+
+        value
+            .parse()
+            .unwrap_or_else(|e| panic!("invalid value for parameter `{name}`: {value} ({e})"))
+    }
+}
+
+impl Display for Params {
+    /// All values including the defaults as `name=value`, which identifies them in cache keys
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, param) in self.declared.iter().enumerate() {
+            let value = self.values.get(param.name).map_or(param.default, |v| v);
+            match i {
+                0 => write!(f, "{}={value}", param.name)?,
+                _ => write!(f, ",{}={value}", param.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lists the parameters with their defaults, for the help of the run command
+pub fn describe(declared: &[Param]) -> String {
+    declared
+        .iter()
+        .map(|p| format!("    {:20} {} (default: {})", p.name, p.help, p.default))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_params() {
+    static DECLARED: &[Param] = &[Param {
+        name: "steps",
+        default: "64",
+        help: "number of steps",
+        validate: positive,
+    }];
+
+    let mut params = Params::new(DECLARED);
+    assert_eq!(params.get::<usize>("steps"), 64);
+    params.set("steps", "6").unwrap();
+    assert_eq!(params.get::<usize>("steps"), 6);
+    assert!(params.set("stpes", "6").is_err());
+    assert!(params.set("steps", "abc").is_err());
+    assert!(params.set("steps", "0").is_err());
+    assert_eq!(params.get::<usize>("steps"), 6);
+    assert_eq!(params.to_string(), "steps=6");
+}
@@ -0,0 +1,280 @@
+//! Interactive shell
+//!
+//! Reads commands line by line and keeps the loaded day, its input and the parameters between
+//! them, so a part can be tried with other inputs or parameters without recompiling. The input
+//! is kept as text, the parts parse it again whenever they run.
+
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::bench;
+use crate::days::{self, Day};
+use crate::params::Params;
+use crate::util::{cancel, checked, progress};
+
+static HELP: &str = "\
+load <day> [FILE]       load a day with the input in FILE, or with the input of the day
+part <n>                solve part n of the loaded input
+time [RUNS]             time every part RUNS times (5 by default)
+set param NAME=VALUE    override a parameter of the loaded day
+params                  list the values of the parameters
+show                    print the parsed input
+help                    print this message
+quit                    leave the repl";
+
+struct Loaded {
+    day: &'static Day,
+    input: String,
+    params: Params,
+}
+
+#[derive(Default)]
+struct Session {
+    loaded: Option<Loaded>,
+    /// time limit of a part, or of timing a part
+    timeout: Option<Duration>,
+}
+
+impl Session {
+    /// Executes a command, returns what to print
+    fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        match words[..] {
+            [] => Ok(String::new()),
+            ["help"] => Ok(HELP.to_string()),
+            ["load", day] => self.load(day, None),
+            ["load", day, path] => self.load(day, Some(path)),
+            ["part", part] => match part.parse() {
+                Ok(part) => self.part(part),
+                Err(_) => Err(format!("invalid part: {part}")),
+            },
+            ["time"] => self.time(5),
+            ["time", runs] => match runs.parse() {
+                Ok(0) | Err(_) => Err(format!("invalid number of runs: {runs}")),
+                Ok(runs) => self.time(runs),
+            },
+            ["set", "param", param] => {
+                let loaded = self.loaded()?;
+                let (name, value) = param
+                    .split_once('=')
+                    .ok_or_else(|| format!("invalid parameter: {param}, expected NAME=VALUE"))?;
+                loaded.params.set(name, value)?;
+                Ok(loaded.params.to_string())
+            }
+            ["params"] => {
+                let loaded = self.loaded()?;
+                match loaded.day.params {
+                    [] => Ok(format!("day {} has no parameters", loaded.day.day)),
+                    _ => Ok(loaded.params.to_string()),
+                }
+            }
+            ["show"] => self.show(),
+            _ => Err(format!("unknown command: {line}, try help")),
+        }
+    }
+
+    fn loaded(&mut self) -> Result<&mut Loaded, String> {
+        self.loaded
+            .as_mut()
+            .ok_or_else(|| "nothing is loaded, try load <day> [FILE]".to_string())
+    }
+
+    fn load(&mut self, day: &str, path: Option<&str>) -> Result<String, String> {
+        let day = day
+            .parse()
+            .ok()
+            .and_then(days::get)
+            .ok_or_else(|| format!("there is no day {day}"))?;
+        let (input, source) = match path {
+            Some(path) => (
+                fs::read_to_string(path).map_err(|e| format!("failed to read {path}: {e}"))?,
+                path.to_string(),
+            ),
+            None => (day.input.to_string(), "the input of the day".to_string()),
+        };
+
+        // the parameters stay when another input of the same day is loaded
+        let params = match self.loaded.take() {
+            Some(loaded) if loaded.day.day == day.day => loaded.params,
+            _ => Params::new(day.params),
+        };
+        let summary = format!(
+            "day {} ({}): {} lines from {source}",
+            day.day,
+            day.title,
+            input.lines().count()
+        );
+        self.loaded = Some(Loaded { day, input, params });
+        Ok(summary)
+    }
+
+    fn part(&mut self, part: usize) -> Result<String, String> {
+        let timeout = self.timeout;
+        let Loaded { day, input, params } = self.loaded()?;
+        if part == 0 || part > day.parts.len() {
+            return Err(format!("day {} has no part {part}", day.day));
+        }
+
+        let solution = day.parts[part - 1];
+        progress::start(format!("d{:2}.{part}", day.day));
+        let now = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| match timeout {
+            Some(timeout) => {
+                let (input, params) = (input.clone(), params.clone());
+                cancel::with_timeout(timeout, move || {
+                    checked::catch(|| solution(&input, &params))
+                })
+            }
+            None => Some(checked::catch(|| solution(input, params))),
+        }));
+        progress::finish();
+        match result {
+            Ok(Some(Ok(Ok(answer)))) => Ok(format!(
+                "d{:2}.{part}: {answer}\t{}",
+                day.day,
+                bench::time(now.elapsed().as_micros() as u64)
+            )),
+            Ok(Some(Ok(Err(e)))) => Err(e),
+            Ok(Some(Err(overflow))) => Err(overflow.to_string()),
+            Ok(None) => Err(format!(
+                "d{:2}.{part} timed out after {:?}",
+                day.day,
+                timeout.unwrap()
+            )),
+            Err(_) => Err(format!("d{:2}.{part} panicked", day.day)),
+        }
+    }
+
+    fn time(&mut self, runs: usize) -> Result<String, String> {
+        let timeout = self.timeout;
+        let Loaded { day, input, params } = self.loaded()?;
+        let day: &'static Day = day;
+        Ok((1..=day.parts.len())
+            .map(|part| {
+                // the timeout is for all the runs of a part
+                let timing = match timeout {
+                    Some(timeout) => {
+                        let (input, params) = (input.clone(), params.clone());
+                        cancel::with_timeout(timeout, move || {
+                            bench::measure(day, &input, &params, part, runs)
+                        })
+                    }
+                    None => Some(bench::measure(day, input, params, part, runs)),
+                };
+                match timing {
+                    Some(Some(timing)) => timing.to_string(),
+                    Some(None) => format!("d{:2}.{part}: failed", day.day),
+                    None => format!(
+                        "d{:2}.{part}: timed out after {:?}",
+                        day.day,
+                        timeout.unwrap()
+                    ),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn show(&mut self) -> Result<String, String> {
+        let Loaded { day, input, .. } = self.loaded()?;
+        match day.show {
+            Some(show) => panic::catch_unwind(AssertUnwindSafe(|| show(input)))
+                .map_err(|_| "failed to parse the input".to_string()),
+            None => Ok(format!(
+                "{} lines, {} bytes, day {} can't show its parsed input",
+                input.lines().count(),
+                input.len(),
+                day.day
+            )),
+        }
+    }
+}
+
+/// Executes the commands read from `input` until it ends or the quit command, giving up on parts
+/// which take longer than `timeout`
+pub(crate) fn repl(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    timeout: Option<Duration>,
+) -> io::Result<()> {
+    let mut session = Session {
+        timeout,
+        ..Session::default()
+    };
+    let mut line = String::new();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return writeln!(output);
+        }
+
+        match line.trim() {
+            "quit" | "exit" => return Ok(()),
+            line => match session.execute(line) {
+                Ok(out) if out.is_empty() => (),
+                Ok(out) => writeln!(output, "{out}")?,
+                Err(e) => writeln!(output, "error: {e}")?,
+            },
+        }
+    }
+}
+
+#[test]
+fn test_repl() {
+    let mut session = Session::default();
+    assert!(session.execute("part 1").is_err());
+    assert_eq!(
+        session.execute("load 19 data/d19t"),
+        Ok("day 19 (Aplenty): 17 lines from data/d19t".to_string())
+    );
+    assert!(session
+        .execute("part 1")
+        .unwrap()
+        .starts_with("d19.1: 19114\t"));
+    assert!(session
+        .execute("part 2")
+        .unwrap()
+        .starts_with("d19.2: 167409079868000\t"));
+    assert!(session.execute("part 3").is_err());
+    assert!(session.execute("set param steps=100").is_err());
+    assert!(session.execute("show").unwrap().contains("\"in\": ["));
+
+    session.execute("load 21 data/d21t").unwrap();
+    assert_eq!(
+        session.execute("set param steps=6"),
+        Ok("steps=6,infinite_steps=26501365".to_string())
+    );
+    assert!(session
+        .execute("part 1")
+        .unwrap()
+        .starts_with("d21.1: 16\t"));
+    assert!(session.execute("frobnicate").is_err());
+
+    let mut session = Session {
+        timeout: Some(Duration::from_nanos(1)),
+        ..Session::default()
+    };
+    session.execute("load 19").unwrap();
+    assert_eq!(
+        session.execute("part 1"),
+        Err("d19.1 timed out after 1ns".to_string())
+    );
+    assert!(session.execute("time 1").unwrap().contains("timed out"));
+
+    let mut output = Vec::new();
+    repl(
+        "load 2x\nhelp\nquit\npart 1\n".as_bytes(),
+        &mut output,
+        None,
+    )
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("> error: there is no day 2x\n> load <day>"));
+    assert!(output.ends_with("leave the repl\n> "));
+}
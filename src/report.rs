@@ -0,0 +1,156 @@
+//! Table of the results
+//!
+//! Solves every part once and lists the answers and times, as aligned text or as a markdown
+//! table for the README. Answers can be redacted, since puzzle answers are specific to the
+//! input and shouldn't be shared.
+
+use std::{
+    fmt::Write as _,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::bench;
+use crate::days::{Answer, Day};
+use crate::params::Params;
+use crate::util::cancel;
+
+pub(crate) struct Row {
+    day: u8,
+    title: &'static str,
+    part: usize,
+    /// the answer and the time it took, or why there is no answer
+    result: Result<(Answer, Duration), &'static str>,
+}
+
+/// Solves every part of `days`, giving up on parts which take longer than `timeout`
+pub(crate) fn collect<'a>(
+    days: impl Iterator<Item = &'a Day>,
+    timeout: Option<Duration>,
+) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for day in days {
+        for (i, &part) in day.parts.iter().enumerate() {
+            let params = Params::new(day.params);
+            let input = day.input;
+            let now = Instant::now();
+            let answer = panic::catch_unwind(AssertUnwindSafe(|| match timeout {
+                Some(timeout) => cancel::with_timeout(timeout, move || part(input, &params)),
+                None => Some(part(input, &params)),
+            }));
+            rows.push(Row {
+                day: day.day,
+                title: day.title,
+                part: i + 1,
+                result: match answer {
+                    Ok(Some(Ok(answer))) => Ok((answer, now.elapsed())),
+                    Ok(Some(Err(_))) => Err("failed"),
+                    Ok(None) => Err("timed out"),
+                    Err(_) => Err("failed"),
+                },
+            });
+        }
+    }
+    rows
+}
+
+// the answer and time columns of a row
+fn cells(row: &Row, redact: bool) -> (String, String) {
+    match &row.result {
+        Ok((answer, time)) => (
+            match redact {
+                true => "redacted".to_string(),
+                false => answer.to_string(),
+            },
+            bench::time(time.as_micros() as u64),
+        ),
+        Err(reason) => (reason.to_string(), "-".to_string()),
+    }
+}
+
+fn total(rows: &[Row]) -> String {
+    let total: Duration = rows
+        .iter()
+        .filter_map(|r| r.result.as_ref().ok())
+        .map(|r| r.1)
+        .sum();
+    bench::time(total.as_micros() as u64)
+}
+
+pub(crate) fn markdown(rows: &[Row], redact: bool) -> String {
+    let mut table = String::from(
+        "| Day | Title | Part | Answer | Time |\n\
+         |----:|-------|-----:|-------:|-----:|\n",
+    );
+    for row in rows {
+        let (answer, time) = cells(row, redact);
+        // the title is only shown on the first part of a day
+        let (day, title) = match row.part {
+            1 => (row.day.to_string(), row.title.replace('|', "\\|")),
+            _ => (String::new(), String::new()),
+        };
+        let _ = writeln!(
+            table,
+            "| {day} | {title} | {} | {answer} | {time} |",
+            row.part
+        );
+    }
+    let _ = write!(table, "| | **Total** | | | **{}** |", total(rows));
+    table
+}
+
+pub(crate) fn plain(rows: &[Row], redact: bool) -> String {
+    let title_width = rows.iter().map(|r| r.title.len()).max().unwrap_or(0);
+    let mut table = String::new();
+    for row in rows {
+        let (answer, time) = cells(row, redact);
+        let _ = writeln!(
+            table,
+            "d{:2}.{}  {:title_width$}  {answer:>16}  {time:>10}",
+            row.day, row.part, row.title
+        );
+    }
+    let _ = write!(
+        table,
+        "{:>w$}  {:>10}",
+        "total",
+        total(rows),
+        w = 5 + 2 + title_width + 2 + 16
+    );
+    table
+}
+
+#[test]
+fn test_markdown() {
+    let rows = [
+        Row {
+            day: 1,
+            title: "Trebuchet?!",
+            part: 1,
+            result: Ok((Answer::Int(142), Duration::from_micros(120))),
+        },
+        Row {
+            day: 1,
+            title: "Trebuchet?!",
+            part: 2,
+            result: Ok((Answer::Int(281), Duration::from_micros(2500))),
+        },
+        Row {
+            day: 22,
+            title: "Sand Slabs",
+            part: 1,
+            result: Err("failed"),
+        },
+    ];
+    assert_eq!(
+        markdown(&rows, false),
+        "\
+| Day | Title | Part | Answer | Time |
+|----:|-------|-----:|-------:|-----:|
+| 1 | Trebuchet?! | 1 | 142 | 120us |
+|  |  | 2 | 281 | 2.5ms |
+| 22 | Sand Slabs | 1 | failed | - |
+| | **Total** | | | **2.6ms** |"
+    );
+    assert!(!markdown(&rows, true).contains("142"));
+}
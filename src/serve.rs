@@ -0,0 +1,348 @@
+//! HTTP API
+//!
+//! Serves the solvers to other local tools, one request at a time since the solvers share the
+//! global state of the runner. With `--timeout` a solver which timed out is only cancelled, so
+//! it may keep running on its worker next to the solver of the next request until it reaches a
+//! cancellation check.
+//!
+//! - `GET /days` lists the days with their number of parts and their parameters
+//! - `POST /days/{d}/parts/{p}?name=value&...` solves a part for the input in the body, or for
+//!   the input of the day if the body is empty, and returns the answer and the time it took
+//!
+//! Answers are returned as strings, since they don't always fit into a JSON number.
+
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::days::{self, DAYS};
+use crate::params::Params;
+use crate::util::{cancel, checked, json};
+
+/// the largest accepted body, far larger than any input
+const MAX_BODY: usize = 16 << 20;
+
+/// how long a client may take to send its request or to read the response, so a client which
+/// stays silent can't block the other requests
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    /// the path without the query
+    path: String,
+    /// the query as (name, value)
+    query: Vec<(String, String)>,
+    body: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    /// JSON
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":{}}}", json::string(message)),
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        504 => "Gateway Timeout",
+        _ => "Unknown",
+    }
+}
+
+/// Reads a request, `Err` is the response to a malformed one
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let bad = |message: &str| Response::error(400, message);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| bad(&e.to_string()))?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (words.next(), words.next(), words.next())
+    else {
+        return Err(bad("malformed request line"));
+    };
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .map_err(|e| bad(&e.to_string()))?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad("invalid Content-Length"))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(Response::error(413, "the body is too large"));
+    }
+
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| bad(&e.to_string()))?;
+    let body = String::from_utf8(body).map_err(|_| bad("the body isn't UTF-8"))?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (name, value) = p.split_once('=').unwrap_or((p, ""));
+            Ok((decode(name)?, decode(value)?))
+        })
+        .collect::<Result<_, String>>()
+        .map_err(|e| bad(&e))?;
+
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query,
+        body,
+    })
+}
+
+// decodes the `%XX` escapes and the `+` for spaces of a query
+fn decode(s: &str) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [iter.next(), iter.next()];
+                let byte = match hex {
+                    [Some(hi), Some(lo)] => std::str::from_utf8(&[hi, lo])
+                        .ok()
+                        .and_then(|h| u8::from_str_radix(h, 16).ok()),
+                    _ => None,
+                };
+                bytes.push(byte.ok_or_else(|| format!("invalid escape in the query: {s}"))?);
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| format!("the query isn't UTF-8: {s}"))
+}
+
+fn list_days() -> Response {
+    let mut body = String::from("[");
+    for (i, day) in DAYS.iter().enumerate() {
+        let params = day
+            .params
+            .iter()
+            .map(|p| {
+                format!(
+                    "{{\"name\":{},\"default\":{},\"help\":{}}}",
+                    json::string(p.name),
+                    json::string(p.default),
+                    json::string(p.help)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        if i > 0 {
+            body.push(',');
+        }
+        let _ = write!(
+            body,
+            "{{\"day\":{},\"parts\":{},\"params\":[{params}]}}",
+            day.day,
+            day.parts.len()
+        );
+    }
+    body.push(']');
+    Response::ok(body)
+}
+
+fn solve(request: &Request, day: &str, part: &str, timeout: Option<Duration>) -> Response {
+    let Some(day) = day.parse().ok().and_then(days::get) else {
+        return Response::error(404, &format!("there is no day {day}"));
+    };
+    let Some((part, &solution)) = part
+        .parse::<usize>()
+        .ok()
+        .and_then(|p| Some((p, day.parts.get(p.checked_sub(1)?)?)))
+    else {
+        return Response::error(404, &format!("day {} has no part {part}", day.day));
+    };
+
+    let mut params = Params::new(day.params);
+    for (name, value) in &request.query {
+        if let Err(e) = params.set(name, value) {
+            return Response::error(400, &e);
+        }
+    }
+    let input = match request.body.is_empty() {
+        true => day.input.to_string(),
+        false => request.body.clone(),
+    };
+
+    let now = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| match timeout {
+        Some(timeout) => cancel::with_timeout(timeout, move || {
+            checked::catch(|| solution(&input, &params))
+        }),
+        None => Some(checked::catch(|| solution(&input, &params))),
+    }));
+    let micros = now.elapsed().as_micros();
+
+    match result {
+        Ok(Some(Ok(Ok(answer)))) => Response::ok(format!(
+            "{{\"day\":{},\"part\":{part},\"answer\":{},\"micros\":{micros}}}",
+            day.day,
+            json::string(&answer.to_string())
+        )),
+        Ok(Some(Ok(Err(e)))) => Response::error(400, &e),
+        Ok(Some(Err(overflow))) => Response::error(422, &overflow.to_string()),
+        Ok(None) => Response::error(504, &format!("timed out after {:?}", timeout.unwrap())),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or(payload.downcast_ref::<String>().map(|s| s.as_str()))
+                .unwrap_or("the solver panicked");
+            Response::error(500, message)
+        }
+    }
+}
+
+fn handle(request: &Request, timeout: Option<Duration>) -> Response {
+    let segments: Vec<_> = request.path.split('/').skip(1).collect();
+    match (request.method.as_str(), &segments[..]) {
+        ("GET", ["days"]) => list_days(),
+        ("POST", ["days", day, "parts", part]) => solve(request, day, part, timeout),
+        (_, ["days"] | ["days", _, "parts", _]) => Response::error(405, "method not allowed"),
+        _ => Response::error(404, &format!("no such endpoint: {}", request.path)),
+    }
+}
+
+fn respond(stream: &TcpStream, timeout: Option<Duration>) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let response = match read_request(&mut reader) {
+        Ok(request) => {
+            let response = handle(&request, timeout);
+            eprintln!("{} {} {}", request.method, request.path, response.status);
+            response
+        }
+        Err(response) => response,
+    };
+
+    let mut writer = stream;
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.body.len(),
+        response.body
+    )?;
+    writer.flush()
+}
+
+/// Serves the API on `port` of localhost, only returns if the port can't be bound
+pub(crate) fn serve(port: u16, timeout: Option<Duration>) -> io::Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    eprintln!("serving on http://{}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| respond(&stream, timeout));
+        if let Err(e) = result {
+            eprintln!("failed to respond: {e}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_handle() {
+    let raw = "POST /days/1/parts/1?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 12\r\n\r\n\
+               1abc2\npqr3s\n";
+    let request = read_request(&mut raw.as_bytes()).unwrap();
+    assert_eq!(request.path, "/days/1/parts/1");
+    assert_eq!(request.query, [("x".to_string(), "1".to_string())]);
+    assert_eq!(request.body, "1abc2\npqr3s\n");
+    // day 1 has no parameters
+    assert_eq!(handle(&request, None).status, 400);
+
+    let raw = "POST /days/21/parts/1?na%6De=a%3Db+c HTTP/1.1\r\n\r\n";
+    let query = read_request(&mut raw.as_bytes()).unwrap().query;
+    assert_eq!(query, [("name".to_string(), "a=b c".to_string())]);
+    let raw = "POST /days/21/parts/1?steps=%6 HTTP/1.1\r\n\r\n";
+    assert_eq!(read_request(&mut raw.as_bytes()).unwrap_err().status, 400);
+
+    let request = Request {
+        query: vec![],
+        ..request
+    };
+    let response = handle(&request, None);
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("\"answer\":\"45\""));
+
+    let request = Request {
+        method: "GET".to_string(),
+        path: "/days".to_string(),
+        query: vec![],
+        body: String::new(),
+    };
+    let response = handle(&request, None);
+    assert!(response
+        .body
+        .starts_with("[{\"day\":1,\"parts\":2,\"params\":[]}"));
+
+    for (method, path, status) in [
+        ("GET", "/days/1/parts/1", 405),
+        ("POST", "/days/1/parts/3", 404),
+    ] {
+        let request = Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: vec![],
+            body: String::new(),
+        };
+        assert_eq!(handle(&request, None).status, status);
+    }
+
+    let request = Request {
+        method: "POST".to_string(),
+        path: "/days/21/parts/1".to_string(),
+        query: vec![("steps".to_string(), "abc".to_string())],
+        body: String::new(),
+    };
+    assert_eq!(handle(&request, None).status, 400);
+}
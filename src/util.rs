@@ -1,6 +1,25 @@
 //! Utility functions/traits for the challenges
 //!
 
+pub mod alloc;
+pub mod bigint;
+pub mod bits;
+pub mod cancel;
+pub mod checked;
+pub mod diag;
+pub mod explain;
+pub mod frames;
+pub mod graph;
+pub mod hash;
+pub mod json;
+pub mod log;
+pub mod progress;
+#[cfg(test)]
+pub mod prop;
+pub mod rational;
+pub mod rng;
+pub mod scale;
+
 /// Rotates 90 degrees clockwise, assumes grid is a rectangle
 pub trait Rotate {
     fn rotate(&self) -> Self;
@@ -0,0 +1,158 @@
+//! Memory usage of the solvers
+//!
+//! [`Counting`] is the global allocator, it passes everything on to the system allocator and,
+//! once [`enable`]d, counts the allocations and the bytes in use. The runner calls [`reset`]
+//! before a part and reads the [`Stats`] of the part afterwards. While disabled the counting
+//! only costs an atomic load per allocation.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::{self, Display},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// bytes in use right now
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+/// most bytes in use since the last reset
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// bytes in use at the last reset, the peak of a part is relative to it
+static BASE: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+pub struct Counting;
+
+impl Counting {
+    fn grow(&self, size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        TOTAL.fetch_add(size, Ordering::Relaxed);
+        COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, size: usize) {
+        // memory allocated before counting was enabled can be freed afterwards
+        let _ = CURRENT.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_sub(size))
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Ordering::Relaxed) {
+            self.shrink(layout.size());
+        }
+    }
+
+    // counted as freeing the old block and allocating the new one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() && ENABLED.load(Ordering::Relaxed) {
+            self.shrink(layout.size());
+            self.grow(new_size);
+        }
+        new
+    }
+}
+
+/// What a part allocated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// most bytes in use at once, on top of what was in use before
+    pub peak: usize,
+    /// bytes allocated in total
+    pub total: usize,
+    /// number of allocations
+    pub count: usize,
+}
+
+/// Starts counting the allocations
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Starts counting the allocations of the next part
+pub fn reset() {
+    let current = CURRENT.load(Ordering::Relaxed);
+    BASE.store(current, Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+    TOTAL.store(0, Ordering::Relaxed);
+    COUNT.store(0, Ordering::Relaxed);
+}
+
+/// The allocations since the last [`reset`]
+pub fn stats() -> Stats {
+    Stats {
+        peak: PEAK
+            .load(Ordering::Relaxed)
+            .saturating_sub(BASE.load(Ordering::Relaxed)),
+        total: TOTAL.load(Ordering::Relaxed),
+        count: COUNT.load(Ordering::Relaxed),
+    }
+}
+
+// a size with a binary unit, like 1.5 MiB
+fn bytes(n: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = n as f64;
+    let mut unit = 0;
+    while size >= 1024. && unit < UNITS.len() - 1 {
+        size /= 1024.;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{n} B"),
+        _ => format!("{size:.1} {}", UNITS[unit]),
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "peak {}, {} in {} allocations",
+            bytes(self.peak),
+            bytes(self.total),
+            self.count
+        )
+    }
+}
+
+#[test]
+fn test_stats() {
+    assert_eq!(bytes(512), "512 B");
+    assert_eq!(bytes(3 << 19), "1.5 MiB");
+
+    // the other tests allocate at the same time, so only lower bounds hold
+    enable();
+    reset();
+    let v = vec![0u8; 1 << 20];
+    drop(std::hint::black_box(v));
+    let stats = stats();
+    assert!(stats.peak >= 1 << 20);
+    assert!(stats.total >= 1 << 20);
+    assert!(stats.count >= 1);
+}
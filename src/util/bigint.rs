@@ -0,0 +1,517 @@
+//! Arbitrary-precision integers
+//!
+//! A small std-only [`BigUint`] stored as little-endian `u32` limbs and a signed [`BigInt`] on
+//! top of it. Schoolbook algorithms throughout, which is plenty for the few hundred bits the
+//! solvers need when `i128` isn't enough.
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    iter::{Product, Sum},
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    str::FromStr,
+};
+
+/// Error of parsing a number which isn't made of decimal digits
+#[derive(Debug, PartialEq)]
+pub struct ParseBigIntError;
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal number")
+    }
+}
+
+/// An unsigned integer of any size
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    // little-endian, without leading zero limbs, so zero has no limbs
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |n, &limb| (n << 32) | limb as u128),
+        )
+    }
+
+    fn bits(&self) -> usize {
+        self.limbs.last().map_or(0, |top| {
+            self.limbs.len() * 32 - top.leading_zeros() as usize
+        })
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        self.limbs[i / 32] >> (i % 32) & 1 == 1
+    }
+
+    fn shl1(&mut self) {
+        let mut carry = 0;
+        for limb in self.limbs.iter_mut() {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    fn div_rem_small(&self, rhs: u32) -> (Self, u32) {
+        let mut quot = vec![0; self.limbs.len()];
+        let mut rem = 0u64;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let cur = (rem << 32) | limb as u64;
+            quot[i] = (cur / rhs as u64) as u32;
+            rem = cur % rhs as u64;
+        }
+        (Self::from_limbs(quot), rem as u32)
+    }
+
+    /// The quotient and the remainder, panics if `rhs` is zero
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        match rhs.limbs[..] {
+            [] => panic!("attempt to divide by zero"),
+            [small] => {
+                let (quot, rem) = self.div_rem_small(small);
+                (quot, Self::from(rem))
+            }
+            _ if self < rhs => (Self::default(), self.clone()),
+            _ => {
+                // binary long division, one bit of the quotient at a time
+                let mut quot = vec![0; self.limbs.len()];
+                let mut rem = Self::default();
+                for i in (0..self.bits()).rev() {
+                    rem.shl1();
+                    if self.bit(i) {
+                        match rem.limbs.first_mut() {
+                            Some(low) => *low |= 1,
+                            None => rem.limbs.push(1),
+                        }
+                    }
+                    if rem >= *rhs {
+                        rem = &rem - rhs;
+                        quot[i / 32] |= 1 << (i % 32);
+                    }
+                }
+                (Self::from_limbs(quot), rem)
+            }
+        }
+    }
+
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let rem = a.div_rem(&b).1;
+            a = b;
+            b = rem;
+        }
+        a
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut n: u128) -> Self {
+        let mut limbs = Vec::new();
+        while n != 0 {
+            limbs.push(n as u32);
+            n >>= 32;
+        }
+        Self { limbs }
+    }
+}
+
+macro_rules! from_unsigned {
+    ( $($t:ty),* ) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(n: $t) -> Self {
+                    Self::from(n as u128)
+                }
+            }
+        )*
+    };
+}
+
+from_unsigned!(u32, u64, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &decimal(self))
+    }
+}
+
+fn decimal(n: &BigUint) -> String {
+    const CHUNK: u32 = 1_000_000_000;
+
+    // split into chunks of nine digits, starting with the least significant one
+    let mut chunks = Vec::new();
+    let mut n = n.clone();
+    while !n.is_zero() {
+        let (quot, rem) = n.div_rem_small(CHUNK);
+        chunks.push(rem);
+        n = quot;
+    }
+
+    let mut chunks = chunks.into_iter().rev();
+    let mut out = chunks.next().unwrap_or(0).to_string();
+    for chunk in chunks {
+        out.push_str(&format!("{chunk:09}"));
+    }
+    out
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        // nine digits at a time fit into a limb
+        let chunk = BigUint::from(1_000_000_000u32);
+        let head = s.len() % 9;
+        let mut n = BigUint::from(s[..head].parse::<u32>().unwrap_or(0));
+        for i in (head..s.len()).step_by(9) {
+            n = n * &chunk + BigUint::from(s[i..i + 9].parse::<u32>().unwrap());
+        }
+        Ok(n)
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: Self) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(rhs.limbs.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..self.limbs.len().max(rhs.limbs.len()) {
+            let sum = carry
+                + self.limbs.get(i).copied().unwrap_or(0) as u64
+                + rhs.limbs.get(i).copied().unwrap_or(0) as u64;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    /// Panics if `rhs` is greater than `self`, like the unsigned integers do in debug builds
+    fn sub(self, rhs: Self) -> BigUint {
+        assert!(*self >= *rhs, "attempt to subtract with overflow");
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let mut diff = limb as i64 - rhs.limbs.get(i).copied().unwrap_or(0) as i64 - borrow;
+            borrow = (diff < 0) as i64;
+            diff += borrow << 32;
+            limbs.push(diff as u32);
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: Self) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let cur = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: Self) -> BigUint {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: Self) -> BigUint {
+        self.div_rem(rhs).1
+    }
+}
+
+/// A signed integer of any size
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    // never set for zero, so every value has a single representation
+    neg: bool,
+    mag: BigUint,
+}
+
+impl BigInt {
+    fn new(neg: bool, mag: BigUint) -> Self {
+        Self {
+            neg: neg && !mag.is_zero(),
+            mag,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.mag
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let mag = self.mag.to_u128()?;
+        match self.neg {
+            true => 0i128.checked_sub_unsigned(mag),
+            false => i128::try_from(mag).ok(),
+        }
+    }
+
+    /// The truncated quotient and the remainder with the sign of `self`, like the primitive
+    /// integers
+    pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
+        let (quot, rem) = self.mag.div_rem(&rhs.mag);
+        (
+            Self::new(self.neg != rhs.neg, quot),
+            Self::new(self.neg, rem),
+        )
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(n: i128) -> Self {
+        Self::new(n < 0, BigUint::from(n.unsigned_abs()))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(mag: BigUint) -> Self {
+        Self::new(false, mag)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(mag) => Ok(Self::new(true, mag.parse()?)),
+            None => Ok(Self::new(false, s.parse()?)),
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, false) => self.mag.cmp(&other.mag),
+            (true, true) => other.mag.cmp(&self.mag),
+            (neg, _) => match neg {
+                true => Ordering::Less,
+                false => Ordering::Greater,
+            },
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.neg, "", &decimal(&self.mag))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.neg, self.mag.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: Self) -> BigInt {
+        if self.neg == rhs.neg {
+            return BigInt::new(self.neg, &self.mag + &rhs.mag);
+        }
+        // the signs differ, so the larger magnitude wins
+        match self.mag >= rhs.mag {
+            true => BigInt::new(self.neg, &self.mag - &rhs.mag),
+            false => BigInt::new(rhs.neg, &rhs.mag - &self.mag),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: Self) -> BigInt {
+        self + &-rhs
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: Self) -> BigInt {
+        BigInt::new(self.neg != rhs.neg, &self.mag * &rhs.mag)
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: Self) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: Self) -> BigInt {
+        self.div_rem(rhs).1
+    }
+}
+
+// the operators on owned values forward to the ones on references
+macro_rules! owned {
+    ( $t:ty: $( $trait:ident $fn:ident ),* ) => {
+        $(
+            impl $trait for $t {
+                type Output = $t;
+
+                fn $fn(self, rhs: Self) -> $t {
+                    $trait::$fn(&self, &rhs)
+                }
+            }
+
+            impl $trait<&$t> for $t {
+                type Output = $t;
+
+                fn $fn(self, rhs: &$t) -> $t {
+                    $trait::$fn(&self, rhs)
+                }
+            }
+
+            impl $trait<$t> for &$t {
+                type Output = $t;
+
+                fn $fn(self, rhs: $t) -> $t {
+                    $trait::$fn(self, &rhs)
+                }
+            }
+        )*
+    };
+}
+
+owned!(BigUint: Add add, Sub sub, Mul mul, Div div, Rem rem);
+owned!(BigInt: Add add, Sub sub, Mul mul, Div div, Rem rem);
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, n| acc + n)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::from(1u32), |acc, n| acc * n)
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -&self
+    }
+}
+
+#[test]
+fn test_bigint() {
+    let a = BigInt::from(i128::MAX);
+    let b = BigInt::from(-12345678901234567890i128);
+    let product = &a * &b;
+    assert_eq!(
+        product.to_string(),
+        "-2100508418878994820155996804711992239607501260191919306030"
+    );
+    assert_eq!(product.to_i128(), None);
+    assert_eq!(&product / &b, a);
+    // truncated division, the remainder has the sign of the dividend
+    assert_eq!(
+        &(&product + &BigInt::from(7)) % &a,
+        BigInt::from(7 - i128::MAX)
+    );
+    assert_eq!((&product - &product).to_i128(), Some(0));
+    assert!(product < b && b < a);
+    assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+    assert_eq!(
+        BigUint::from(u128::MAX).gcd(&BigUint::from(1u128 << 100)),
+        BigUint::from(1u32)
+    );
+    assert_eq!(format!("{:>6}", BigInt::from(-42)), "   -42");
+    assert_eq!(product.to_string().parse(), Ok(product));
+    assert_eq!("-0".parse(), Ok(BigInt::from(0)));
+    assert_eq!("12a".parse::<BigInt>(), Err(ParseBigIntError));
+}
@@ -0,0 +1,261 @@
+//! Bit packed sets and grids
+//!
+//! [`BitSet`] is a set of small integers, one bit each. [`BitGrid`] is a set of cells of a
+//! rectangle, stored as rows of 64 bit words, so a whole grid can be moved by a cell or combined
+//! with another grid a word at a time. Cellular simulations step all their cells at once this
+//! way instead of visiting them one by one.
+
+use std::ops::{BitAnd, BitOr};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A set of the integers below a fixed bound
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// An empty set for the integers below `len`
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(BITS)],
+        }
+    }
+
+    /// Adds `i`, returns false if it was already in the set
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, bit) = (i / BITS, 1 << (i % BITS));
+        let new = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        new
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words[i / BITS] & (1 << (i % BITS)) != 0
+    }
+
+    /// The number of integers in the set
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+/// A set of the cells of a `width` x `height` grid
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    /// words per row
+    stride: usize,
+    /// the rows one after another, x is bit `x % 64` of word `x / 64` of the row
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// An empty grid, a grid without columns stays empty
+    pub fn new(width: usize, height: usize) -> Self {
+        // at least one word per row, the moves go through the rows in chunks of `stride`
+        let stride = width.div_ceil(BITS).max(1);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn empty_like(&self) -> Self {
+        Self::new(self.width, self.height)
+    }
+
+    fn index(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of the grid"
+        );
+        (y * self.stride + x / BITS, 1 << (x % BITS))
+    }
+
+    pub fn insert(&mut self, x: usize, y: usize) {
+        let (word, bit) = self.index(x, y);
+        self.words[word] |= bit;
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        let (word, bit) = self.index(x, y);
+        self.words[word] & bit != 0
+    }
+
+    /// The number of cells in the set
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The number of cells of row `y` in the set
+    pub fn count_row(&self, y: usize) -> usize {
+        self.words[y * self.stride..(y + 1) * self.stride]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// The cells in `self` but not in `other`
+    pub fn without(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a & !b)
+    }
+
+    fn zip(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| f(*a, *b))
+                .collect(),
+            ..self.empty_like()
+        }
+    }
+
+    /// Every cell moved up by one, the top row is dropped
+    pub fn up(&self) -> Self {
+        let mut moved = self.empty_like();
+        if self.height == 0 {
+            return moved;
+        }
+        let len = self.words.len() - self.stride;
+        moved.words[..len].copy_from_slice(&self.words[self.stride..]);
+        moved
+    }
+
+    /// Every cell moved down by one, the bottom row is dropped
+    pub fn down(&self) -> Self {
+        let mut moved = self.empty_like();
+        if self.height == 0 {
+            return moved;
+        }
+        let len = self.words.len() - self.stride;
+        moved.words[self.stride..].copy_from_slice(&self.words[..len]);
+        moved
+    }
+
+    /// Every cell moved left by one, the left column is dropped
+    pub fn left(&self) -> Self {
+        let mut moved = self.empty_like();
+        for (row, moved) in self
+            .words
+            .chunks_exact(self.stride)
+            .zip(moved.words.chunks_exact_mut(self.stride))
+        {
+            for i in 0..self.stride {
+                let carry = row.get(i + 1).map_or(0, |next| next << (BITS - 1));
+                moved[i] = row[i] >> 1 | carry;
+            }
+        }
+        moved
+    }
+
+    /// Every cell moved right by one, the right column is dropped
+    pub fn right(&self) -> Self {
+        let mut moved = self.empty_like();
+        for (row, moved) in self
+            .words
+            .chunks_exact(self.stride)
+            .zip(moved.words.chunks_exact_mut(self.stride))
+        {
+            for i in 0..self.stride {
+                let carry = match i {
+                    0 => 0,
+                    _ => row[i - 1] >> (BITS - 1),
+                };
+                moved[i] = row[i] << 1 | carry;
+            }
+            // drop what was moved past the right column
+            if !self.width.is_multiple_of(BITS) {
+                moved[self.stride - 1] &= (1 << (self.width % BITS)) - 1;
+            }
+        }
+        moved
+    }
+
+    /// The cells next to a cell of the set, horizontally or vertically
+    pub fn neighbours(&self) -> Self {
+        &(&self.up() | &self.down()) | &(&self.left() | &self.right())
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, other: Self) -> BitGrid {
+        self.zip(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, other: Self) -> BitGrid {
+        self.zip(other, |a, b| a | b)
+    }
+}
+
+#[test]
+fn test_bit_grid() {
+    // wide enough for the moves to cross a word
+    let mut grid = BitGrid::new(70, 3);
+    grid.insert(63, 1);
+    grid.insert(69, 0);
+
+    let right = grid.right();
+    assert!(right.contains(64, 1));
+    assert_eq!(right.count(), 1);
+    let left = grid.left();
+    assert!(left.contains(62, 1) && left.contains(68, 0));
+    assert!(left.right().contains(63, 1));
+    assert!(grid.up().contains(63, 0));
+    assert_eq!(grid.up().count(), 1);
+    assert_eq!(grid.down().count_row(2), 1);
+
+    let neighbours = grid.neighbours();
+    assert_eq!(neighbours.count(), 4 + 2);
+    assert!((&neighbours & &grid).is_empty());
+    assert_eq!((&neighbours | &grid).without(&grid), neighbours);
+
+    let mut set = BitSet::new(130);
+    assert!(set.insert(129));
+    assert!(!set.insert(129));
+    assert!(set.contains(129) && !set.contains(65));
+    assert_eq!(set.count(), 1);
+}
+
+#[test]
+fn test_bit_grid_without_columns() {
+    let grid = BitGrid::new(0, 3);
+    assert_eq!(grid.width(), 0);
+    assert!(grid.left().is_empty() && grid.right().is_empty());
+    assert!(grid.neighbours().is_empty());
+    assert_eq!(grid.count_row(2), 0);
+}
+
+#[test]
+fn test_bit_grid_without_rows() {
+    let grid = BitGrid::new(70, 0);
+    assert_eq!(grid.height(), 0);
+    assert!(grid.up().is_empty() && grid.down().is_empty());
+    assert!(grid.neighbours().is_empty());
+    assert_eq!(grid.count(), 0);
+}
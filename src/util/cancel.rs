@@ -0,0 +1,103 @@
+//! Cooperative cancellation
+//!
+//! [`with_timeout`] runs a solver on a worker thread and gives up on it after the timeout. The
+//! worker can't be killed, so loops which might not terminate on a bad input call [`check`],
+//! which unwinds the worker once it was cancelled.
+
+use std::{
+    cell::RefCell,
+    panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Once,
+    },
+    thread,
+    time::Duration,
+};
+
+thread_local! {
+    // every worker has its own token, so a cancelled worker stays cancelled while the next one
+    // is already running
+    static TOKEN: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Panic payload unwinding a cancelled worker
+struct Cancelled;
+
+/// Stops the current solver if it was cancelled, a no-op outside of [`with_timeout`]
+pub fn check() {
+    let cancelled = TOKEN.with(|token| {
+        token
+            .borrow()
+            .as_ref()
+            .is_some_and(|t| t.load(Ordering::Relaxed))
+    });
+    if cancelled {
+        panic::panic_any(Cancelled);
+    }
+}
+
+/// Runs `f` on a worker thread, returns `None` and cancels the worker if it takes longer than
+/// `timeout`. Panics of `f` are passed on.
+pub fn with_timeout<T, F>(timeout: Duration, f: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    // cancelled workers unwind silently
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if info.payload().downcast_ref::<Cancelled>().is_none() {
+                default(info);
+            }
+        }));
+    });
+
+    let token = Arc::new(AtomicBool::new(false));
+    let worker_token = token.clone();
+    let (tx, rx) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        TOKEN.with(|token| *token.borrow_mut() = Some(worker_token));
+        // the receiver is gone if the worker timed out
+        let _ = tx.send(f());
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(value) => Some(value),
+        Err(RecvTimeoutError::Timeout) => {
+            token.store(true, Ordering::Relaxed);
+            None
+        }
+        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(payload) => panic::resume_unwind(payload),
+            Ok(()) => unreachable!("the worker finished without sending its result"),
+        },
+    }
+}
+
+#[test]
+fn test_with_timeout() {
+    assert_eq!(with_timeout(Duration::from_secs(10), || 42), Some(42));
+
+    // reports when the worker unwinds, which only happens when it is cancelled
+    struct Stopped(mpsc::Sender<()>);
+    impl Drop for Stopped {
+        fn drop(&mut self) {
+            let _ = self.0.send(());
+        }
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let result = with_timeout(Duration::from_millis(10), move || {
+        let _stopped = Stopped(tx);
+        loop {
+            check();
+            thread::sleep(Duration::from_millis(1));
+        }
+    });
+    assert_eq!(result, None::<()>);
+    assert!(rx.recv_timeout(Duration::from_secs(10)).is_ok());
+}
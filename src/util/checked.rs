@@ -0,0 +1,223 @@
+//! Checked arithmetic mode
+//!
+//! Solvers wrap arithmetic which might overflow in [`Checked`]. Normally it behaves exactly like
+//! the plain operators and `as` casts. With `--checked`, an overflow panics with an [`Overflow`]
+//! naming the operation and its operands, which [`catch`] turns into an error for the runner to
+//! report together with the day and part.
+
+use std::{
+    cell::Cell,
+    fmt::{self, Display},
+    ops::{Add, Mul, Sub},
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static OVERFLOWED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// checked mode for the current thread only, so tests don't turn it on for each other
+    static LOCAL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Panic payload of an overflow in checked mode
+#[derive(Debug)]
+pub struct Overflow {
+    op: &'static str,
+    expr: String,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "overflow in `{}`: {}", self.op, self.expr)
+    }
+}
+
+/// Enables checked mode for all following operations
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+
+    // overflows are reported by the runner, so the default message is only printed for other panics
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if info.payload().downcast_ref::<Overflow>().is_none() {
+            default(info);
+        }
+    }));
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) || LOCAL.get()
+}
+
+/// Like [`catch`], but in checked mode on the current thread whether or not it's enabled
+#[cfg(test)]
+pub fn catch_checked<T, F: FnOnce() -> T>(f: F) -> Result<T, Overflow> {
+    // restores the mode of the thread even if `f` panics with something else than an overflow
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            LOCAL.set(self.0);
+        }
+    }
+
+    let _restore = Restore(LOCAL.replace(true));
+    catch(f)
+}
+
+/// Returns true if any overflow was caught
+pub fn overflowed() -> bool {
+    OVERFLOWED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, in checked mode an overflow is returned as an error instead of panicking
+pub fn catch<T, F: FnOnce() -> T>(f: F) -> Result<T, Overflow> {
+    if !enabled() {
+        return Ok(f());
+    }
+
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| match payload.downcast() {
+        Ok(overflow) => {
+            OVERFLOWED.store(true, Ordering::Relaxed);
+            *overflow
+        }
+        Err(payload) => panic::resume_unwind(payload),
+    })
+}
+
+fn overflow(op: &'static str, expr: String) -> ! {
+    panic::panic_any(Overflow { op, expr })
+}
+
+/// Integers supporting checked arithmetic
+pub trait Int:
+    Copy + Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! int {
+    ( $($t:ty),* ) => {
+        $(
+            impl Int for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+int!(usize, isize, u64, i64);
+
+/// Conversions which might not fit into the target type
+pub trait Cast<U>: Copy + Display {
+    /// the conversion of `as`
+    fn cast(self) -> U;
+    fn checked_cast(self) -> Option<U>;
+}
+
+macro_rules! cast {
+    ( $( $from:ty => $to:ty ),* ) => {
+        $(
+            impl Cast<$to> for $from {
+                fn cast(self) -> $to {
+                    self as $to
+                }
+                fn checked_cast(self) -> Option<$to> {
+                    <$to>::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+cast!(usize => isize, isize => usize, i64 => usize);
+
+/// A value whose arithmetic is checked in checked mode, `op` names the operation in reports
+#[derive(Debug, Clone, Copy)]
+pub struct Checked<T> {
+    value: T,
+    op: &'static str,
+}
+
+impl<T> Checked<T> {
+    pub fn new(value: T, op: &'static str) -> Self {
+        Self { value, op }
+    }
+
+    pub fn get(self) -> T {
+        self.value
+    }
+
+    pub fn cast<U>(self) -> Checked<U>
+    where
+        T: Cast<U>,
+    {
+        let value = match enabled() {
+            true => self.value.checked_cast().unwrap_or_else(|| {
+                let target = std::any::type_name::<U>();
+                overflow(self.op, format!("{} as {target}", self.value))
+            }),
+            false => self.value.cast(),
+        };
+        Checked::new(value, self.op)
+    }
+}
+
+macro_rules! op {
+    ( $trait:ident, $fn:ident, $checked:ident, $sym:literal ) => {
+        impl<T: Int> $trait<T> for Checked<T> {
+            type Output = Self;
+
+            fn $fn(self, rhs: T) -> Self {
+                let value = match enabled() {
+                    true => self.value.$checked(rhs).unwrap_or_else(|| {
+                        overflow(
+                            self.op,
+                            format!(concat!("{} ", $sym, " {}"), self.value, rhs),
+                        )
+                    }),
+                    false => $trait::$fn(self.value, rhs),
+                };
+                Self::new(value, self.op)
+            }
+        }
+
+        impl<T: Int> $trait for Checked<T> {
+            type Output = Self;
+
+            fn $fn(self, rhs: Self) -> Self {
+                $trait::$fn(self, rhs.value)
+            }
+        }
+    };
+}
+
+op!(Add, add, checked_add, "+");
+op!(Sub, sub, checked_sub, "-");
+op!(Mul, mul, checked_mul, "*");
+
+#[test]
+fn test_checked() {
+    let mul = || (Checked::new(usize::MAX / 2, "test") * 3).get();
+    let overflow = catch_checked(mul).unwrap_err();
+    assert!(!enabled());
+    assert_eq!(
+        overflow.to_string(),
+        format!("overflow in `test`: {} * 3", usize::MAX / 2)
+    );
+
+    let cast = Checked::new(-1isize, "test").cast::<usize>().get();
+    assert_eq!(cast, usize::MAX);
+}
@@ -0,0 +1,235 @@
+//! Input validation diagnostics
+//!
+//! Each day can check the format of an input without running the solver. A [`Checker`] collects
+//! the problems as [`Diagnostic`]s, which [`render`] prints with the line, column and a caret
+//! snippet pointing at the problem.
+
+use std::{fmt::Write, str::FromStr};
+
+/// lines longer than this are cut around the problem in the snippet
+const MAX_WIDTH: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// line of the problem, starting at 0
+    pub line: usize,
+    /// column of the problem in bytes, starting at 0
+    pub col: usize,
+    /// number of underlined characters
+    pub len: usize,
+    pub msg: String,
+}
+
+/// Collects the problems of an input.
+///
+/// Problems are reported at a span, which has to be a slice of the checked input, so the line
+/// and column can be derived from its position.
+pub struct Checker<'a> {
+    inp: &'a str,
+    diags: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+    pub fn new(inp: &'a str) -> Self {
+        Self {
+            inp,
+            diags: Vec::new(),
+        }
+    }
+
+    /// The lines of the input, reports an empty input
+    pub fn lines(&mut self) -> Vec<&'a str> {
+        let lines: Vec<_> = self.inp.lines().collect();
+        if lines.is_empty() {
+            self.error(self.inp, "input is empty");
+        }
+        lines
+    }
+
+    /// The lines of the input, split into blocks at empty lines
+    pub fn blocks(&mut self) -> Vec<Vec<&'a str>> {
+        let mut blocks = vec![Vec::new()];
+        for line in self.lines() {
+            match line.is_empty() {
+                true => blocks.push(Vec::new()),
+                false => blocks.last_mut().unwrap().push(line),
+            }
+        }
+        blocks
+    }
+
+    pub fn error(&mut self, span: &str, msg: impl Into<String>) {
+        let offset = (span.as_ptr() as usize).wrapping_sub(self.inp.as_ptr() as usize);
+        assert!(offset <= self.inp.len(), "span is not part of the input");
+
+        let before = &self.inp[..offset];
+        self.diags.push(Diagnostic {
+            line: before.matches('\n').count(),
+            col: offset - before.rfind('\n').map_or(0, |i| i + 1),
+            len: span.lines().next().map_or(0, |l| l.chars().count()),
+            msg: msg.into(),
+        });
+    }
+
+    /// Reports an error right after `span`, for things missing at the end of it
+    pub fn error_after(&mut self, span: &str, msg: impl Into<String>) {
+        self.error(&span[span.len()..], msg);
+    }
+
+    /// Parses `span`, reports what was expected if it can't be parsed
+    pub fn parse<T: FromStr>(&mut self, span: &str, what: &str) -> Option<T> {
+        let parsed = span.parse().ok();
+        if parsed.is_none() {
+            match span.is_empty() {
+                true => self.error(span, format!("expected {what}")),
+                false => self.error(span, format!("expected {what}, found `{span}`")),
+            }
+        }
+        parsed
+    }
+
+    /// Splits `span` at the first `delim`, reports the missing delimiter at the end of `span`
+    pub fn split_once(&mut self, span: &'a str, delim: &str) -> Option<(&'a str, &'a str)> {
+        let split = span.split_once(delim);
+        if split.is_none() {
+            self.error_after(span, format!("expected `{delim}`"));
+        }
+        split
+    }
+
+    /// Checks that `span` consists of the `allowed` characters only
+    pub fn chars(&mut self, span: &'a str, allowed: &str) {
+        for (i, c) in span.char_indices() {
+            if !allowed.contains(c) {
+                self.error(
+                    &span[i..i + c.len_utf8()],
+                    format!("unexpected character `{c}`"),
+                );
+            }
+        }
+    }
+
+    /// Checks that `lines` form a rectangular grid of the `allowed` characters
+    pub fn grid(&mut self, lines: &[&'a str], allowed: &str) {
+        let width = lines.first().map_or(0, |l| l.len());
+        for line in lines {
+            self.chars(line, allowed);
+            if line.len() != width {
+                self.error_after(
+                    line,
+                    format!("expected {width} columns, found {}", line.len()),
+                );
+            }
+        }
+    }
+
+    /// Checks that `c` occurs exactly once in `lines`
+    pub fn single(&mut self, lines: &[&'a str], c: char) {
+        let found: Vec<_> = lines
+            .iter()
+            .flat_map(|l| l.match_indices(c).map(|(i, s)| &l[i..i + s.len()]))
+            .collect();
+        match found[..] {
+            [] => {
+                if let Some(first) = lines.first() {
+                    self.error(&first[..0], format!("expected a `{c}`"));
+                }
+            }
+            [_] => (),
+            [_, ref extra @ ..] => {
+                for span in extra {
+                    self.error(span, format!("expected only one `{c}`"));
+                }
+            }
+        }
+    }
+
+    /// The problems in the order of their position in the input
+    pub fn finish(mut self) -> Vec<Diagnostic> {
+        self.diags.sort_by_key(|d| (d.line, d.col));
+        self.diags
+    }
+}
+
+/// Renders the diagnostics of the input read from `name`, similar to the errors of rustc
+pub fn render(inp: &str, name: &str, diags: &[Diagnostic]) -> String {
+    let lines: Vec<_> = inp.lines().collect();
+    let gutter = diags
+        .iter()
+        .map(|d| (d.line + 1).to_string().len())
+        .max()
+        .unwrap_or(1);
+
+    let mut out = String::new();
+    for diag in diags {
+        let line = lines.get(diag.line).copied().unwrap_or("");
+        let (snippet, col) = cut(line, diag.col);
+        let _ = writeln!(out, "error: {}", diag.msg);
+        let _ = writeln!(
+            out,
+            "{:gutter$}--> {name}:{}:{}",
+            "",
+            diag.line + 1,
+            diag.col + 1
+        );
+        let _ = writeln!(out, "{:gutter$} |", "");
+        let _ = writeln!(out, "{:>gutter$} | {snippet}", diag.line + 1);
+        let _ = writeln!(
+            out,
+            "{:gutter$} | {:col$}{}\n",
+            "",
+            "",
+            "^".repeat(diag.len.max(1))
+        );
+    }
+    out
+}
+
+// cuts long lines around `col`, returns the snippet and the column in the snippet
+fn cut(line: &str, col: usize) -> (String, usize) {
+    if line.len() <= MAX_WIDTH {
+        return (line.to_string(), col);
+    }
+
+    let mut start = col.saturating_sub(MAX_WIDTH / 2).min(line.len());
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + MAX_WIDTH).min(line.len());
+    while !line.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let prefix = if start > 0 { "..." } else { "" };
+    let suffix = if end < line.len() { "..." } else { "" };
+    (
+        format!("{prefix}{}{suffix}", &line[start..end]),
+        col - start + prefix.len(),
+    )
+}
+
+#[test]
+fn test_checker() {
+    let inp = "1 2\n3 x\n";
+    let mut checker = Checker::new(inp);
+    for line in checker.lines() {
+        for token in line.split_whitespace() {
+            checker.parse::<u32>(token, "a number");
+        }
+    }
+    let diags = checker.finish();
+
+    assert_eq!(
+        diags,
+        vec![Diagnostic {
+            line: 1,
+            col: 2,
+            len: 1,
+            msg: "expected a number, found `x`".to_string()
+        }]
+    );
+    assert_eq!(
+        render(inp, "inp", &diags),
+        "error: expected a number, found `x`\n --> inp:2:3\n  |\n2 | 3 x\n  |   ^\n\n"
+    );
+}
@@ -0,0 +1,186 @@
+//! Derivations of answers
+//!
+//! A bare number is hard to trust, so some days can explain how they got to their answer. An
+//! [`Explanation`] is a table with a row per step of the solution, like a row per card with its
+//! matches and copies, and a summary of how the rows add up to the answer. It can be rendered as
+//! aligned text to check by hand or as JSON for other tools.
+
+use std::{fmt::Write as _, str::FromStr};
+
+use super::json;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Text(String),
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Int(n as i128)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Text(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown explanation format `{s}`, expected text or json"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// how the rows add up to the answer
+    summary: String,
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Explanation {
+    pub fn new(summary: impl Into<String>, columns: &[&'static str]) -> Self {
+        Self {
+            summary: summary.into(),
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row with a value for every column
+    pub fn push(&mut self, row: Vec<Value>) {
+        assert_eq!(
+            row.len(),
+            self.columns.len(),
+            "a row needs a value per column"
+        );
+        self.rows.push(row);
+    }
+
+    pub fn write(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.to_text(),
+            Format::Json => self.to_json(),
+        }
+    }
+
+    // the summary and the table, with the numbers aligned to the right
+    fn to_text(&self) -> String {
+        let cell = |value: &Value| match value {
+            Value::Int(n) => n.to_string(),
+            Value::Text(s) => s.clone(),
+        };
+        let widths: Vec<_> = (0..self.columns.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .map(|row| cell(&row[i]).len())
+                    .chain([self.columns[i].len()])
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+
+        let mut text = format!("{}\n", self.summary);
+        let header: Vec<_> = self
+            .columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, width)| format!("{column:width$}"))
+            .collect();
+        if !header.is_empty() {
+            let _ = writeln!(text, "  {}", header.join("  ").trim_end());
+        }
+        for row in &self.rows {
+            let cells: Vec<_> = row
+                .iter()
+                .zip(&widths)
+                .map(|(value, width)| match value {
+                    Value::Int(n) => format!("{n:>width$}"),
+                    Value::Text(s) => format!("{s:width$}"),
+                })
+                .collect();
+            let _ = writeln!(text, "  {}", cells.join("  ").trim_end());
+        }
+        text.pop();
+        text
+    }
+
+    // an object with the summary and the rows as objects keyed by the columns
+    fn to_json(&self) -> String {
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<_> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, value)| {
+                        let value = match value {
+                            Value::Int(n) => n.to_string(),
+                            Value::Text(s) => json::string(s),
+                        };
+                        format!("{}:{value}", json::string(column))
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            })
+            .collect();
+        format!(
+            "{{\"summary\":{},\"rows\":[{}]}}",
+            json::string(&self.summary),
+            rows.join(",")
+        )
+    }
+}
+
+#[test]
+fn test_explanation() {
+    let mut explanation = Explanation::new("the sum of the points", &["card", "matches", "won"]);
+    explanation.push(vec![1.into(), 4.into(), "48 83 86 17".into()]);
+    explanation.push(vec![12.into(), 0.into(), "".into()]);
+
+    assert_eq!(
+        explanation.write(Format::Text),
+        "\
+the sum of the points
+  card  matches  won
+     1        4  48 83 86 17
+    12        0"
+    );
+    assert_eq!(
+        explanation.write(Format::Json),
+        "{\"summary\":\"the sum of the points\",\"rows\":[\
+         {\"card\":1,\"matches\":4,\"won\":\"48 83 86 17\"},\
+         {\"card\":12,\"matches\":0,\"won\":\"\"}]}"
+    );
+    assert_eq!(
+        Explanation::new("no rows", &[]).write(Format::Text),
+        "no rows"
+    );
+    assert!("yaml".parse::<Format>().is_err());
+}
@@ -0,0 +1,157 @@
+//! Frame capture for the iterative simulations
+//!
+//! Simulations call [`emit`] with a label and a closure rendering the current state as ascii.
+//! The closure is only evaluated if capturing is enabled and the frame isn't skipped by the
+//! stride or the frame limit.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+static CAPTURE: Mutex<Option<FrameCapture>> = Mutex::new(None);
+/// avoids locking the mutex in the hot loops if nothing is captured
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Where the captured frames are written to
+#[derive(Debug, Clone)]
+pub enum Target {
+    /// One file per frame, named `<label>-<frame>.txt`
+    Dir(PathBuf),
+    /// All frames in a single file, separated by terminal clear codes,
+    /// so the log can be replayed with `cat`
+    Log(PathBuf),
+}
+
+#[derive(Debug)]
+pub struct FrameCapture {
+    target: Target,
+    limit: Option<usize>,
+    stride: usize,
+    /// number of (emitted, written) frames per label
+    counts: HashMap<String, (usize, usize)>,
+    log: Option<BufWriter<File>>,
+}
+
+impl FrameCapture {
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            limit: None,
+            stride: 1,
+            counts: HashMap::new(),
+            log: None,
+        }
+    }
+
+    /// Only write the first `limit` frames of each label
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only write every `stride`th frame
+    pub fn stride(mut self, stride: usize) -> Self {
+        self.stride = stride.max(1);
+        self
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        match &self.target {
+            Target::Dir(dir) => fs::create_dir_all(dir),
+            Target::Log(path) => {
+                self.log = Some(BufWriter::new(File::create(path)?));
+                Ok(())
+            }
+        }
+    }
+
+    fn should_write(&mut self, label: &str) -> Option<usize> {
+        let (emitted, written) = self.counts.entry(label.to_string()).or_insert((0, 0));
+        let frame = *emitted;
+        *emitted += 1;
+
+        if frame % self.stride != 0 || self.limit.is_some_and(|limit| *written >= limit) {
+            return None;
+        }
+        *written += 1;
+
+        Some(frame)
+    }
+
+    fn write(&mut self, label: &str, frame: usize, content: &str) -> io::Result<()> {
+        match (&self.target, &mut self.log) {
+            (Target::Dir(dir), _) => {
+                fs::write(dir.join(format!("{label}-{frame:06}.txt")), content)
+            }
+            (Target::Log(_), Some(log)) => {
+                write!(log, "\x1b[2J\x1b[H--- {label} frame {frame} ---\n{content}")?;
+                if !content.ends_with('\n') {
+                    writeln!(log)?;
+                }
+                Ok(())
+            }
+            (Target::Log(_), None) => Err(io::Error::other("frame log isn't open")),
+        }
+    }
+}
+
+/// Enables frame capturing for all following simulations
+pub fn init(mut capture: FrameCapture) -> io::Result<()> {
+    capture.open()?;
+    *CAPTURE.lock().unwrap() = Some(capture);
+    ENABLED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Flushes the frame log, if there is one
+pub fn finish() {
+    if let Some(Some(log)) = CAPTURE.lock().unwrap().as_mut().map(|c| c.log.as_mut()) {
+        if let Err(e) = log.flush() {
+            eprintln!("failed to write frame log: {e}");
+        }
+    }
+}
+
+/// Emits a frame for `label`, `render` is only called if the frame gets written.
+///
+/// If writing fails, capturing is disabled and the error is printed once.
+pub fn emit<F: FnOnce() -> String>(label: &str, render: F) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let mut guard = CAPTURE.lock().unwrap();
+    let capture = match guard.as_mut() {
+        Some(capture) => capture,
+        None => return,
+    };
+
+    if let Some(frame) = capture.should_write(label) {
+        if let Err(e) = capture.write(label, frame, &render()) {
+            eprintln!("failed to write frame, disabling capture: {e}");
+            ENABLED.store(false, Ordering::Relaxed);
+            *guard = None;
+        }
+    }
+}
+
+#[test]
+fn test_stride_and_limit() {
+    let mut capture = FrameCapture::new(Target::Dir(PathBuf::new()))
+        .stride(3)
+        .limit(2);
+    let written: Vec<_> = (0..10)
+        .filter_map(|_| capture.should_write("d14"))
+        .collect();
+    assert_eq!(written, vec![0, 3]);
+
+    // labels are counted separately
+    assert_eq!(capture.should_write("d16"), Some(0));
+}
@@ -0,0 +1,231 @@
+//! Graphs with labelled nodes
+//!
+//! [`Graph`] maps the labels of its nodes to dense indices, so solvers can keep per node state
+//! in vectors or bit masks, and stores the edges with their weights in adjacency lists. A graph
+//! can be written as DOT for Graphviz or as GraphML for viewers like Gephi and yEd.
+
+use std::{fmt::Write as _, str::FromStr};
+
+use super::hash::FastMap;
+
+/// How the weight of an edge is shown in the written graph
+pub trait Weight {
+    fn label(&self) -> Option<String>;
+}
+
+impl Weight for () {
+    fn label(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Weight for usize {
+    fn label(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+impl Weight for char {
+    fn label(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dot,
+    GraphMl,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Format::Dot),
+            "graphml" => Ok(Format::GraphMl),
+            _ => Err(format!(
+                "unknown graph format `{s}`, expected dot or graphml"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Graph<W> {
+    directed: bool,
+    labels: Vec<String>,
+    indices: FastMap<String, usize>,
+    /// as (from, to, weight)
+    edges: Vec<(usize, usize, W)>,
+    /// the edges of every node, as indices into `edges`
+    adjacent: Vec<Vec<usize>>,
+}
+
+impl<W> Graph<W> {
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            labels: Vec::new(),
+            indices: FastMap::default(),
+            edges: Vec::new(),
+            adjacent: Vec::new(),
+        }
+    }
+
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    /// The index of the node `label`, which is added if it doesn't exist yet
+    pub fn node(&mut self, label: &str) -> usize {
+        if let Some(&i) = self.indices.get(label) {
+            return i;
+        }
+        self.labels.push(label.to_string());
+        self.adjacent.push(Vec::new());
+        self.indices
+            .insert(label.to_string(), self.labels.len() - 1);
+        self.labels.len() - 1
+    }
+
+    /// Adds an edge between the nodes `from` and `to`, adding them if needed
+    pub fn add_edge(&mut self, from: &str, to: &str, weight: W) {
+        let (from, to) = (self.node(from), self.node(to));
+        self.edges.push((from, to, weight));
+        self.adjacent[from].push(self.edges.len() - 1);
+        if !self.directed && from != to {
+            self.adjacent[to].push(self.edges.len() - 1);
+        }
+    }
+
+    /// The index of the node `label`, if there is one
+    pub fn index(&self, label: &str) -> Option<usize> {
+        self.indices.get(label).copied()
+    }
+
+    /// The number of nodes
+    pub fn node_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// The nodes reached by the edges of `node`, with the weights of the edges
+    pub fn neighbours(&self, node: usize) -> impl Iterator<Item = (usize, &W)> {
+        self.adjacent[node].iter().map(move |&e| {
+            let (from, to, weight) = &self.edges[e];
+            match *from == node {
+                true => (*to, weight),
+                false => (*from, weight),
+            }
+        })
+    }
+
+    /// All edges as (from, to, weight), in the order they were added
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, &W)> {
+        self.edges.iter().map(|(from, to, w)| (*from, *to, w))
+    }
+}
+
+impl<W: Weight> Graph<W> {
+    pub fn write(&self, format: Format) -> String {
+        match format {
+            Format::Dot => self.to_dot(),
+            Format::GraphMl => self.to_graphml(),
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let (kind, arrow) = match self.directed {
+            true => ("digraph", "->"),
+            false => ("graph", "--"),
+        };
+
+        let mut dot = format!("{kind} {{\n");
+        for (i, label) in self.labels.iter().enumerate() {
+            let _ = writeln!(dot, "    {i} [label={}];", quote(label));
+        }
+        for (from, to, weight) in &self.edges {
+            let _ = match weight.label() {
+                Some(label) => writeln!(dot, "    {from} {arrow} {to} [label={}];", quote(&label)),
+                None => writeln!(dot, "    {from} {arrow} {to};"),
+            };
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_graphml(&self) -> String {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n  \
+             <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"string\"/>\n",
+        );
+        let _ = writeln!(
+            xml,
+            "  <graph id=\"G\" edgedefault=\"{}\">",
+            match self.directed {
+                true => "directed",
+                false => "undirected",
+            }
+        );
+        for (i, label) in self.labels.iter().enumerate() {
+            let _ = writeln!(
+                xml,
+                "    <node id=\"n{i}\"><data key=\"label\">{}</data></node>",
+                escape(label)
+            );
+        }
+        for (from, to, weight) in &self.edges {
+            let _ = match weight.label() {
+                Some(label) => writeln!(
+                    xml,
+                    "    <edge source=\"n{from}\" target=\"n{to}\"><data key=\"weight\">{}</data></edge>",
+                    escape(&label)
+                ),
+                None => writeln!(xml, "    <edge source=\"n{from}\" target=\"n{to}\"/>"),
+            };
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+#[test]
+fn test_graph() {
+    let mut graph = Graph::undirected();
+    graph.add_edge("jqt", "rhn", ());
+    graph.add_edge("jqt", "xhk", ());
+    graph.add_edge("rhn", "xhk", ());
+
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.index("xhk"), Some(2));
+    let mut neighbours: Vec<_> = graph.neighbours(1).map(|(n, _)| n).collect();
+    neighbours.sort();
+    assert_eq!(neighbours, [0, 2]);
+    assert_eq!(
+        graph.write(Format::Dot),
+        "graph {\n    0 [label=\"jqt\"];\n    1 [label=\"rhn\"];\n    2 [label=\"xhk\"];\n    \
+         0 -- 1;\n    0 -- 2;\n    1 -- 2;\n}\n"
+    );
+
+    let mut graph = Graph::directed();
+    graph.add_edge("AAA", "B<B", 'L');
+    assert_eq!(graph.neighbours(1).count(), 0);
+    let xml = graph.write(Format::GraphMl);
+    assert!(xml.contains("edgedefault=\"directed\""));
+    assert!(xml.contains("<data key=\"label\">B&lt;B</data>"));
+    assert!(xml.contains("<edge source=\"n0\" target=\"n1\"><data key=\"weight\">L</data></edge>"));
+}
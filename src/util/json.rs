@@ -0,0 +1,32 @@
+//! Writing JSON
+//!
+//! The few JSON documents the runner writes are built with `format!`, only strings need escaping.
+
+use std::fmt::Write as _;
+
+/// `s` as a quoted JSON string
+pub fn string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[test]
+fn test_string() {
+    assert_eq!(string("a\"b\n"), "\"a\\\"b\\n\"");
+    assert_eq!(string("\u{1}"), "\"\\u0001\"");
+}
@@ -0,0 +1,164 @@
+//! Trace logging
+//!
+//! Solvers log with the [`debug!`] and [`trace!`] macros, using their day as the target, e.g.
+//! `trace!("d22", "dropping {brick:?}")`. Nothing is logged by default: `-v` enables debug and
+//! `-vv` trace messages of all targets, `AOC_LOG` sets the levels per target like
+//! `AOC_LOG=d22=trace,d05=debug`, or of all targets with a plain level like `AOC_LOG=info`.
+//! Messages are only formatted if they are logged.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown log level `{s}`")),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        f.pad(name)
+    }
+}
+
+/// The most verbose level logged per target
+#[derive(Debug, Default, PartialEq)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Level)>,
+}
+
+impl Filter {
+    /// Parses a comma separated list of `target=level` and plain levels for all targets
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Filter::default();
+        for directive in spec.split(',').filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter.targets.push((target.to_string(), level.parse()?)),
+                None => filter.default = Some(directive.parse()?),
+            }
+        }
+        Ok(filter)
+    }
+
+    fn level(&self, target: &str) -> Option<Level> {
+        self.targets
+            .iter()
+            .rev()
+            .find(|(t, _)| t == target)
+            .map(|(_, level)| *level)
+            .or(self.default)
+    }
+
+    fn max(&self) -> Option<Level> {
+        self.targets
+            .iter()
+            .map(|(_, l)| *l)
+            .chain(self.default)
+            .max()
+    }
+}
+
+static FILTER: OnceLock<Filter> = OnceLock::new();
+// the most verbose level of any target, so disabled messages cost a single atomic load
+static MAX: AtomicU8 = AtomicU8::new(0);
+
+/// Sets up logging with `-v` given `verbosity` times and the value of `AOC_LOG`, which takes
+/// precedence
+pub fn init(verbosity: u8, spec: Option<&str>) -> Result<(), String> {
+    let mut filter = match spec {
+        Some(spec) => Filter::parse(spec)?,
+        None => Filter::default(),
+    };
+    if filter.default.is_none() {
+        filter.default = match verbosity {
+            0 => None,
+            1 => Some(Level::Debug),
+            _ => Some(Level::Trace),
+        };
+    }
+
+    MAX.store(filter.max().map_or(0, |l| l as u8), Ordering::Relaxed);
+    FILTER
+        .set(filter)
+        .map_err(|_| "logging is already set up".to_string())
+}
+
+pub fn enabled(target: &str, level: Level) -> bool {
+    level as u8 <= MAX.load(Ordering::Relaxed)
+        && FILTER
+            .get()
+            .and_then(|f| f.level(target))
+            .is_some_and(|max| level <= max)
+}
+
+#[doc(hidden)]
+pub fn write(target: &str, level: Level, args: fmt::Arguments) {
+    eprintln!("[{level:5} {target}] {args}");
+}
+
+/// Logs a message with the given level and target
+macro_rules! log {
+    ( $level:expr, $target:expr, $($arg:tt)+ ) => {
+        if $crate::util::log::enabled($target, $level) {
+            $crate::util::log::write($target, $level, format_args!($($arg)+));
+        }
+    };
+}
+
+macro_rules! debug {
+    ( $target:expr, $($arg:tt)+ ) => {
+        $crate::util::log::log!($crate::util::log::Level::Debug, $target, $($arg)+)
+    };
+}
+
+macro_rules! trace {
+    ( $target:expr, $($arg:tt)+ ) => {
+        $crate::util::log::log!($crate::util::log::Level::Trace, $target, $($arg)+)
+    };
+}
+
+pub(crate) use {debug, log, trace};
+
+#[test]
+fn test_filter() {
+    let filter = Filter::parse("info,d22=trace,d05=warn").unwrap();
+    assert_eq!(filter.level("d22"), Some(Level::Trace));
+    assert_eq!(filter.level("d05"), Some(Level::Warn));
+    assert_eq!(filter.level("d19"), Some(Level::Info));
+    assert_eq!(filter.max(), Some(Level::Trace));
+
+    assert_eq!(Filter::parse("").unwrap(), Filter::default());
+    assert!(Filter::parse("d22=loud").is_err());
+}
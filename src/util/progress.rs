@@ -0,0 +1,119 @@
+//! Progress of long running solvers
+//!
+//! Solvers call [`report`] in their main loop. While a part is running, the runner shows the
+//! latest report on stderr, redrawn at most every [`INTERVAL`]. Reporting is disabled unless
+//! stderr is a terminal, then [`report`] only costs an atomic load.
+
+use std::{
+    fmt::Write as _,
+    io::{self, IsTerminal, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+const INTERVAL: Duration = Duration::from_millis(100);
+/// the clock is only read every this many reports
+const SKIP: usize = 256;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+static STATE: Mutex<State> = Mutex::new(State {
+    label: None,
+    last: None,
+});
+
+struct State {
+    /// day and part which are running
+    label: Option<String>,
+    /// when the last report was drawn
+    last: Option<Instant>,
+}
+
+/// What a solver has done so far
+#[derive(Debug, Default)]
+pub struct Progress {
+    pub iterations: usize,
+    /// number of explored states, if the solver is a search
+    pub states: Option<usize>,
+    /// the best value found so far, like the longest path or the smallest cut
+    pub best: Option<usize>,
+}
+
+/// Enables reporting if stderr is a terminal
+pub fn init() {
+    ENABLED.store(io::stderr().is_terminal(), Ordering::Relaxed);
+}
+
+/// Starts showing the reports of the part `label`
+pub fn start(label: String) {
+    if ENABLED.load(Ordering::Relaxed) {
+        *STATE.lock().unwrap() = State {
+            label: Some(label),
+            last: None,
+        };
+    }
+}
+
+/// Stops showing reports and clears the last one
+pub fn finish() {
+    if ENABLED.load(Ordering::Relaxed) {
+        let mut state = STATE.lock().unwrap();
+        if state.last.is_some() {
+            eprint!("\r\x1b[K");
+        }
+        *state = State {
+            label: None,
+            last: None,
+        };
+    }
+}
+
+/// Reports the progress of the running part, `progress` is only called when it is shown
+pub fn report(progress: impl FnOnce() -> Progress) {
+    if !ENABLED.load(Ordering::Relaxed)
+        || !CALLS.fetch_add(1, Ordering::Relaxed).is_multiple_of(SKIP)
+    {
+        return;
+    }
+
+    let mut state = STATE.lock().unwrap();
+    let now = Instant::now();
+    let Some(label) = &state.label else {
+        return;
+    };
+    if state.last.is_some_and(|last| now - last < INTERVAL) {
+        return;
+    }
+
+    let line = render(label, &progress());
+    eprint!("\r\x1b[K{line}");
+    let _ = io::stderr().flush();
+    state.last = Some(now);
+}
+
+fn render(label: &str, progress: &Progress) -> String {
+    let mut line = format!("{label}: {} iterations", progress.iterations);
+    if let Some(states) = progress.states {
+        let _ = write!(line, ", {states} states");
+    }
+    if let Some(best) = progress.best {
+        let _ = write!(line, ", best so far {best}");
+    }
+    line
+}
+
+#[test]
+fn test_render() {
+    let progress = Progress {
+        iterations: 12,
+        best: Some(3),
+        ..Default::default()
+    };
+    assert_eq!(
+        render("d25.1", &progress),
+        "d25.1: 12 iterations, best so far 3"
+    );
+}
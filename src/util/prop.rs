@@ -0,0 +1,163 @@
+//! Property based testing
+//!
+//! [`check`] runs a property against randomly generated cases. If a case fails, it is shrunk to a
+//! minimal failing case, which is reported together with the seed to reproduce it.
+//!
+//! The number of cases and the seed can be set with `AOC_PROP_CASES` and `AOC_PROP_SEED`.
+
+use std::{
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+};
+
+use super::rng::Rng;
+
+const DEFAULT_CASES: usize = 200;
+const DEFAULT_SEED: usize = 0x5eed;
+/// cases are generated with a size from 1 to MAX_SIZE, growing with each case
+const MAX_SIZE: usize = 20;
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+// panics inside the property count as failures
+fn run<T, P>(property: &P, case: &T) -> Result<(), String>
+where
+    P: Fn(&T) -> Result<(), String>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(|| property(case))) {
+        Ok(result) => result,
+        Err(e) => Err(
+            match (e.downcast_ref::<String>(), e.downcast_ref::<&str>()) {
+                (Some(msg), _) => format!("panicked: {msg}"),
+                (_, Some(msg)) => format!("panicked: {msg}"),
+                _ => "panicked".to_string(),
+            },
+        ),
+    }
+}
+
+// shrinks a failing case as long as there is a smaller one which still fails
+fn minimize<T, S, P>(mut case: T, mut msg: String, shrink: &S, property: &P) -> (T, String, usize)
+where
+    S: Fn(&T) -> Vec<T>,
+    P: Fn(&T) -> Result<(), String>,
+{
+    let mut shrinks = 0;
+    'shrink: loop {
+        for smaller in shrink(&case) {
+            if let Err(smaller_msg) = run(property, &smaller) {
+                (case, msg) = (smaller, smaller_msg);
+                shrinks += 1;
+                continue 'shrink;
+            }
+        }
+        return (case, msg, shrinks);
+    }
+}
+
+/// Checks `property` for randomly generated cases, panics with the minimal failing case.
+///
+/// `shrink` returns smaller variations of a case, they are tried in order and the first one
+/// that still fails is shrunk further.
+pub fn check<T, G, S, P>(name: &str, generate: G, shrink: S, property: P)
+where
+    T: Debug,
+    G: Fn(&mut Rng, usize) -> T,
+    S: Fn(&T) -> Vec<T>,
+    P: Fn(&T) -> Result<(), String>,
+{
+    let cases = env_or("AOC_PROP_CASES", DEFAULT_CASES);
+    let seed = env_or("AOC_PROP_SEED", DEFAULT_SEED);
+    let mut rng = Rng::seed(seed);
+
+    for i in 0..cases {
+        let size = 1 + i * MAX_SIZE / cases.max(1);
+        let case = generate(&mut rng, size);
+        let msg = match run(&property, &case) {
+            Ok(()) => continue,
+            Err(msg) => msg,
+        };
+
+        let (case, msg, shrinks) = minimize(case, msg, &shrink, &property);
+        panic!(
+            "property '{name}' failed on case {i} (AOC_PROP_SEED={seed}), \
+            shrunk {shrinks} times:\n{case:#?}\n{msg}"
+        );
+    }
+}
+
+/// Checks if `actual` and `expected` are equal, for use in properties
+pub fn assert_eq<T: PartialEq + Debug>(actual: T, expected: T) -> Result<(), String> {
+    match actual == expected {
+        true => Ok(()),
+        false => Err(format!("expected {expected:?}, got {actual:?}")),
+    }
+}
+
+/// All variations of `items` with one element removed
+pub fn shrink_vec<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    (0..items.len())
+        .map(|i| {
+            let mut smaller = items.to_vec();
+            smaller.remove(i);
+            smaller
+        })
+        .collect()
+}
+
+/// All variations of `items` with one element shrunk by `shrink`
+pub fn shrink_each<T: Clone, F: Fn(&T) -> Vec<T>>(items: &[T], shrink: F) -> Vec<Vec<T>> {
+    (0..items.len())
+        .flat_map(|i| {
+            shrink(&items[i]).into_iter().map(move |item| {
+                let mut smaller = items.to_vec();
+                smaller[i] = item;
+                smaller
+            })
+        })
+        .collect()
+}
+
+/// Smaller numbers, down to `min`
+pub fn shrink_usize(n: usize, min: usize) -> Vec<usize> {
+    if n <= min {
+        return Vec::new();
+    }
+    let mut smaller = vec![min, min + (n - min) / 2, n.saturating_sub(1)];
+    smaller.retain(|s| *s >= min && *s < n);
+    smaller.dedup();
+    smaller
+}
+
+#[test]
+fn test_minimize() {
+    let shrink = |v: &Vec<usize>| {
+        let mut smaller = shrink_vec(v);
+        smaller.extend(shrink_each(v, |n| shrink_usize(*n, 0)));
+        smaller
+    };
+    let property = |v: &Vec<usize>| match v.iter().sum::<usize>() < 100 {
+        true => Ok(()),
+        false => Err("sum too large".to_string()),
+    };
+
+    let (case, msg, _) = minimize(vec![90, 80, 70], String::new(), &shrink, &property);
+    assert_eq!(case.iter().sum::<usize>(), 100);
+    assert_eq!(msg, "sum too large");
+}
+
+#[test]
+#[should_panic(expected = "AOC_PROP_SEED")]
+fn test_check_reports_seed() {
+    check(
+        "always fails",
+        |rng, _| rng.next(),
+        |n| shrink_usize(*n, 0),
+        |_| Err("failed".to_string()),
+    );
+}
@@ -0,0 +1,260 @@
+//! Exact rational numbers
+//!
+//! A [`Rational`] is kept normalised: the denominator is positive and shares no factor with the
+//! numerator, so equal values have equal representations. The arithmetic works on `i128` with
+//! checked operations and switches to [`BigInt`]s when a result doesn't fit, so it never
+//! overflows or rounds. Results which fit again are stored as `i128` again.
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use super::bigint::BigInt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational(Repr);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Repr {
+    Small { num: i128, den: i128 },
+    Big { num: BigInt, den: BigInt },
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+impl Rational {
+    /// The fraction `num / den`, panics if `den` is zero
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "denominator of a rational is zero");
+        let g = gcd(num.unsigned_abs(), den.unsigned_abs()) as i128;
+        // the gcd only exceeds i128::MAX for 0 / i128::MIN and i128::MIN / i128::MIN
+        if g < 0 {
+            return Self::from(num / den);
+        }
+        let (num, den) = (num / g, den / g);
+        match den < 0 {
+            true => match (num.checked_neg(), den.checked_neg()) {
+                (Some(num), Some(den)) => Self(Repr::Small { num, den }),
+                _ => Self::big(BigInt::from(num), BigInt::from(den)),
+            },
+            false => Self(Repr::Small { num, den }),
+        }
+    }
+
+    fn big(num: BigInt, den: BigInt) -> Self {
+        assert!(!den.is_zero(), "denominator of a rational is zero");
+        let g = BigInt::from(num.magnitude().gcd(den.magnitude()));
+        let (mut num, mut den) = (&num / &g, &den / &g);
+        if den.is_negative() {
+            (num, den) = (-num, -den);
+        }
+        match (num.to_i128(), den.to_i128()) {
+            (Some(num), Some(den)) => Self(Repr::Small { num, den }),
+            _ => Self(Repr::Big { num, den }),
+        }
+    }
+
+    // numerator and denominator for the arbitrary-precision fallback
+    fn parts(&self) -> (BigInt, BigInt) {
+        match &self.0 {
+            Repr::Small { num, den } => (BigInt::from(*num), BigInt::from(*den)),
+            Repr::Big { num, den } => (num.clone(), den.clone()),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        matches!(self.0, Repr::Small { num: 0, .. })
+    }
+
+    /// The value as an integer, if it is one
+    pub fn to_integer(&self) -> Option<BigInt> {
+        let (num, den) = self.parts();
+        (den == BigInt::from(1)).then_some(num)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self(Repr::Small { num: n, den: 1 })
+    }
+}
+
+impl Add for &Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Rational {
+        if let (Repr::Small { num: a, den: b }, Repr::Small { num: c, den: d }) = (&self.0, &rhs.0)
+        {
+            // a/b + c/d = (a * d/g + c * b/g) / (b * d/g) with g = gcd(b, d)
+            let g = gcd(*b as u128, *d as u128) as i128;
+            let small = || {
+                let num = a.checked_mul(d / g)?.checked_add(c.checked_mul(b / g)?)?;
+                Some(Rational::new(num, b.checked_mul(d / g)?))
+            };
+            if let Some(sum) = small() {
+                return sum;
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.parts(), rhs.parts());
+        Rational::big(&(&a * &d) + &(&c * &b), &b * &d)
+    }
+}
+
+impl Sub for &Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Rational {
+        self + &-rhs
+    }
+}
+
+impl Mul for &Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Rational {
+        if let (Repr::Small { num: a, den: b }, Repr::Small { num: c, den: d }) = (&self.0, &rhs.0)
+        {
+            // cancelling crosswise first keeps the result normalised and the factors small
+            let g1 = gcd(a.unsigned_abs(), *d as u128) as i128;
+            let g2 = gcd(c.unsigned_abs(), *b as u128) as i128;
+            let small = || {
+                let num = (a / g1).checked_mul(c / g2)?;
+                Some(Rational::new(num, (b / g2).checked_mul(d / g1)?))
+            };
+            if let Some(product) = small() {
+                return product;
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.parts(), rhs.parts());
+        Rational::big(&a * &c, &b * &d)
+    }
+}
+
+impl Div for &Rational {
+    type Output = Rational;
+
+    /// Panics if `rhs` is zero
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Rational {
+        let reciprocal = match &rhs.0 {
+            Repr::Small { num, den } => Rational::new(*den, *num),
+            Repr::Big { num, den } => Rational::big(den.clone(), num.clone()),
+        };
+        self * &reciprocal
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        match &self.0 {
+            Repr::Small { num, den } => match num.checked_neg() {
+                Some(num) => Rational(Repr::Small { num, den: *den }),
+                None => Rational::big(-BigInt::from(*num), BigInt::from(*den)),
+            },
+            Repr::Big { num, den } => Rational(Repr::Big {
+                num: -num,
+                den: den.clone(),
+            }),
+        }
+    }
+}
+
+// the owned operators forward to the ones on references
+macro_rules! owned {
+    ( $( $trait:ident $fn:ident ),* ) => {
+        $(
+            impl $trait for Rational {
+                type Output = Rational;
+
+                fn $fn(self, rhs: Self) -> Rational {
+                    $trait::$fn(&self, &rhs)
+                }
+            }
+        )*
+    };
+}
+
+owned!(Add add, Sub sub, Mul mul, Div div);
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        -&self
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the denominators are positive, so comparing crosswise keeps the order
+        if let (Repr::Small { num: a, den: b }, Repr::Small { num: c, den: d }) =
+            (&self.0, &other.0)
+        {
+            if let (Some(lhs), Some(rhs)) = (a.checked_mul(*d), c.checked_mul(*b)) {
+                return lhs.cmp(&rhs);
+            }
+        }
+
+        let ((a, b), (c, d)) = (self.parts(), other.parts());
+        (&a * &d).cmp(&(&c * &b))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (num, den) = self.parts();
+        match den == BigInt::from(1) {
+            true => num.fmt(f),
+            false => f.pad(&format!("{num}/{den}")),
+        }
+    }
+}
+
+#[test]
+fn test_rational() {
+    let third = Rational::new(2, -6);
+    assert_eq!(third.to_string(), "-1/3");
+    assert_eq!(&third * &Rational::from(-3), Rational::from(1));
+    assert_eq!(
+        (Rational::new(1, 6) + Rational::new(1, 3)).to_string(),
+        "1/2"
+    );
+    assert!(Rational::new(1, 3) > Rational::new(33, 100));
+    assert_eq!(Rational::new(i128::MIN, i128::MIN), Rational::from(1));
+    assert_eq!(Rational::new(0, i128::MIN), Rational::from(0));
+
+    // overflowing i128 falls back to big integers and returns once the value fits again
+    let max = Rational::from(i128::MAX);
+    let square = &max * &max;
+    assert!(square > max);
+    assert_eq!(
+        square.to_string(),
+        "28948022309329048855892746252171976962977213799489202546401021394546514198529"
+    );
+    assert_eq!(&square / &max, max);
+    assert_eq!(&(&max + &max) - &max, max);
+    assert_eq!(-Rational::from(i128::MIN) - Rational::from(1), max);
+    assert_eq!(
+        (&square - &Rational::new(1, 2)).to_string(),
+        "57896044618658097711785492504343953925954427598978405092802042789093028397057/2"
+    );
+    assert_eq!(square.to_integer(), (&max * &max).to_integer());
+    assert!(Rational::new(1, 2).to_integer().is_none());
+}
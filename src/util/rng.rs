@@ -0,0 +1,75 @@
+//! A small xorshift random number generator
+//!
+//! Not suitable for anything where the quality of the randomness matters, but good enough for
+//! karger's algorithm and for generating inputs.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct Rng {
+    state: usize,
+}
+
+impl Rng {
+    pub fn seed(seed: usize) -> Self {
+        // xorshift gets stuck on 0
+        Self {
+            state: if seed == 0 { 0x2545f4914f6cdd1d } else { seed },
+        }
+    }
+
+    pub fn seed_from_epoch() -> Self {
+        let state = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as usize;
+
+        Self::seed(state)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> usize {
+        let mut tmp = self.state;
+        tmp ^= tmp << 13;
+        tmp ^= tmp >> 17;
+        tmp ^= tmp << 5;
+        self.state = tmp;
+
+        tmp
+    }
+
+    /// Returns a number in `lo..hi`, `hi` has to be greater than `lo`
+    pub fn range(&mut self, lo: usize, hi: usize) -> usize {
+        assert!(lo < hi);
+        lo + self.next() % (hi - lo)
+    }
+
+    /// Returns true with a probability of `percent`%
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.next() % 100 < percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next() % items.len()]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.next() % (i + 1));
+        }
+    }
+
+    /// A random lowercase word of `len` characters
+    pub fn word(&mut self, len: usize) -> String {
+        (0..len)
+            .map(|_| (b'a' + self.range(0, 26) as u8) as char)
+            .collect()
+    }
+}
+
+#[test]
+fn test_range() {
+    let mut rng = Rng::seed(0);
+    assert!((0..1000)
+        .map(|_| rng.range(3, 7))
+        .all(|n| (3..7).contains(&n)));
+}
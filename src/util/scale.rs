@@ -0,0 +1,68 @@
+//! Transforms which make inputs bigger
+//!
+//! Real inputs have a fixed size, so to see how a solver scales the days build bigger inputs out
+//! of them, which still have the structure the puzzle promises. The grids are tiled and the
+//! lists of numbered lines are repeated with new numbers.
+
+use std::fmt::Write as _;
+
+/// Repeats `grid` `factor` times in both directions, `cell` maps every cell of the tile at
+/// (x, y), counted in tiles, so cells which may only appear once can be replaced
+pub fn tile(
+    grid: &str,
+    factor: usize,
+    mut cell: impl FnMut((usize, usize), char) -> char,
+) -> String {
+    let mut tiled = String::new();
+    for y in 0..factor {
+        for line in grid.lines() {
+            for x in 0..factor {
+                tiled.extend(line.chars().map(|c| cell((x, y), c)));
+            }
+            tiled.push('\n');
+        }
+    }
+    tiled
+}
+
+/// Repeats the lines `factor` times, lines like `Game 1: ...` are numbered on from 1 with the
+/// numbers aligned like in the original
+pub fn renumber(lines: &str, factor: usize) -> Result<String, String> {
+    let mut scaled = String::new();
+    let mut id = 0;
+    for _ in 0..factor {
+        for line in lines.lines() {
+            let (head, rest) = line
+                .split_once(':')
+                .ok_or_else(|| format!("there is no number in `{line}`"))?;
+            let label = head.split_whitespace().next().unwrap_or_default();
+            let width = head.len().saturating_sub(label.len() + 1);
+            id += 1;
+            let _ = writeln!(scaled, "{label} {id:>width$}:{rest}");
+        }
+    }
+    Ok(scaled)
+}
+
+#[test]
+fn test_scale() {
+    assert_eq!(
+        tile("#.\n.S\n", 2, |t, c| match c {
+            'S' if t != (0, 0) => '.',
+            c => c,
+        }),
+        "#.#.\n.S..\n#.#.\n....\n"
+    );
+    assert_eq!(
+        renumber("Card  1: 41 | 83\nCard  2: 13 | 61\n", 6)
+            .unwrap()
+            .lines()
+            .nth(10),
+        Some("Card 11: 41 | 83")
+    );
+    assert_eq!(
+        renumber("Game 1: 3 blue\n", 2),
+        Ok("Game 1: 3 blue\nGame 2: 3 blue\n".to_string())
+    );
+    assert!(renumber("3 blue\n", 2).is_err());
+}
@@ -0,0 +1,134 @@
+//! Watch mode
+//!
+//! Polls the modification time of an input and runs the day again whenever it changes. Every
+//! line after the first run also shows how the answer and the time differ from the previous run.
+
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::days::{Answer, Day};
+use crate::params::Params;
+use crate::util::{
+    cancel,
+    checked::{self, Overflow},
+    progress,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The outcome of running a part once
+enum Outcome {
+    Solved(Answer, Duration),
+    Overflow(Overflow),
+    /// the parameters don't fit the input
+    Failed(String),
+    /// the solver took longer than the timeout
+    TimedOut(Duration),
+    /// the solver panicked, most likely because the input is incomplete
+    Panicked,
+}
+
+impl Outcome {
+    fn summary(&self) -> String {
+        match self {
+            Outcome::Solved(answer, _) => answer.to_string(),
+            Outcome::Overflow(_) => "an overflow".to_string(),
+            Outcome::Failed(_) => "an error".to_string(),
+            Outcome::TimedOut(_) => "a timeout".to_string(),
+            Outcome::Panicked => "a panic".to_string(),
+        }
+    }
+}
+
+fn solve(day: &Day, inp: &str, params: &Params, part: usize, timeout: Option<Duration>) -> Outcome {
+    let solution = day.parts[part - 1];
+    progress::start(format!("d{:2}.{part}", day.day));
+    let now = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| match timeout {
+        Some(timeout) => {
+            let (inp, params) = (inp.to_string(), params.clone());
+            cancel::with_timeout(timeout, move || checked::catch(|| solution(&inp, &params)))
+        }
+        None => Some(checked::catch(|| solution(inp, params))),
+    }));
+    progress::finish();
+    match result {
+        Ok(Some(Ok(Ok(answer)))) => Outcome::Solved(answer, now.elapsed()),
+        Ok(Some(Ok(Err(e)))) => Outcome::Failed(e),
+        Ok(Some(Err(overflow))) => Outcome::Overflow(overflow),
+        Ok(None) => Outcome::TimedOut(timeout.unwrap()),
+        Err(_) => Outcome::Panicked,
+    }
+}
+
+// the line of a part, compared to the outcome of the previous run if there was one
+fn report(day: u8, part: usize, outcome: &Outcome, previous: Option<&Outcome>) -> String {
+    let line = match outcome {
+        Outcome::Solved(answer, time) => {
+            format!("d{day:2}.{part}: {answer:16}\t{:10}us", time.as_micros())
+        }
+        Outcome::Overflow(overflow) => format!("d{day:2}.{part}: {overflow}"),
+        Outcome::Failed(e) => format!("d{day:2}.{part}: {e}"),
+        Outcome::TimedOut(timeout) => format!("d{day:2}.{part}: timed out after {timeout:?}"),
+        Outcome::Panicked => format!("d{day:2}.{part}: panicked"),
+    };
+
+    let diff = match (outcome, previous) {
+        (_, None) => return line,
+        (Outcome::Solved(answer, time), Some(Outcome::Solved(prev, prev_time))) => {
+            let change = (time.as_secs_f64() / prev_time.as_secs_f64().max(1e-9) - 1.) * 100.;
+            match answer == prev {
+                true => format!("unchanged, {change:+.1}%"),
+                false => format!("was {prev}, {change:+.1}%"),
+            }
+        }
+        (_, Some(prev)) => format!("was {}", prev.summary()),
+    };
+    format!("{line}\t({diff})")
+}
+
+/// Runs `parts` of `day` on the content of `path` whenever it is modified, giving up on parts
+/// which take longer than `timeout`, never returns
+pub(crate) fn watch(
+    day: &Day,
+    path: &Path,
+    parts: &[usize],
+    params: &Params,
+    timeout: Option<Duration>,
+) -> ! {
+    let mut modified = None;
+    let mut previous: Vec<Option<Outcome>> = parts.iter().map(|_| None).collect();
+    let mut runs = 0;
+
+    loop {
+        let current = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_err(|e| e.to_string());
+
+        if modified.as_ref() != Some(&current) {
+            let inp = current
+                .clone()
+                .and_then(|_| fs::read_to_string(path).map_err(|e| e.to_string()));
+            match inp {
+                Err(e) => eprintln!("failed to read {}: {e}", path.display()),
+                Ok(inp) => {
+                    runs += 1;
+                    println!("== run {runs} of {}", path.display());
+                    for (&part, previous) in parts.iter().zip(previous.iter_mut()) {
+                        let outcome = solve(day, &inp, params, part, timeout);
+                        println!("{}", report(day.day, part, &outcome, previous.as_ref()));
+                        *previous = Some(outcome);
+                    }
+                }
+            }
+            modified = Some(current);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}